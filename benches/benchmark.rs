@@ -12,7 +12,7 @@ use rand::{
 };
 
 fn do_encode_benchmark(b: &mut Bencher<'_>, input_data: &[u8]) {
-    b.iter(|| black_box(STANDARD.encode(&input_data)));
+    b.iter(|| black_box(STANDARD.encode(input_data)));
 }
 
 fn do_encode_to_slice_benchmark(b: &mut Bencher<'_>, input_data: &[u8]) {
@@ -21,7 +21,7 @@ fn do_encode_to_slice_benchmark(b: &mut Bencher<'_>, input_data: &[u8]) {
 
     b.iter(|| {
         assert_eq!(
-            STANDARD.encode_to_slice(&mut output, &input_data),
+            STANDARD.encode_to_slice(&mut output, input_data),
             encoded_size
         )
     });
@@ -0,0 +1,366 @@
+use core::fmt;
+
+use crate::tables::{self, INVALID_BYTE};
+
+/// A set of 32 symbols along with the table used to map them back to their values.
+///
+/// Alphabets are built with [`Alphabet::new`], which is a `const fn` so that any problems
+/// with the symbols are caught at compile time, or picked from one of the presets.
+/// ```
+/// use base32::{Alphabet, Encoding};
+///
+/// const ALPHABET: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+/// const ENCODING: Encoding = Encoding::new(ALPHABET, None);
+///
+/// assert_eq!(ENCODING.encode("foobar"), "dtrqysm1f8");
+/// ```
+#[derive(Copy, Clone)]
+pub struct Alphabet {
+    encode: [u8; 32],
+    decode: [u8; 256],
+}
+
+impl Alphabet {
+    /// The alphabet used by [`STANDARD`](crate::STANDARD).
+    pub const STANDARD: Alphabet = Alphabet::from_tables(tables::ENCODE_STD, tables::DECODE_STD);
+
+    /// The alphabet used by [`EXTENDED_HEX`](crate::EXTENDED_HEX).
+    pub const EXTENDED_HEX: Alphabet =
+        Alphabet::from_tables(tables::ENCODE_HEX, tables::DECODE_HEX);
+
+    /// The alphabet used by [`CROCKFORD`](crate::CROCKFORD), which also decodes
+    /// `I` and `L` as `1` and `O` as `0`.
+    pub const CROCKFORD: Alphabet =
+        Alphabet::from_tables(tables::ENCODE_CROCKFORD, tables::DECODE_CROCKFORD);
+
+    /// The alphabet used by [`ZBASE32`](crate::ZBASE32).
+    pub const ZBASE32: Alphabet =
+        Alphabet::from_tables(tables::ENCODE_ZBASE32, tables::DECODE_ZBASE32);
+
+    /// The alphabet used by [`bech32`](crate::bech32), ordered so that symbols that look
+    /// alike mostly differ in a single bit.
+    pub const BECH32: Alphabet = Alphabet::new(b"qpzry9x8gf2tvdw0s3jn54khce6mua7l");
+
+    /// The alphabet used by [`geohash`](crate::geohash), which leaves out `a`, `i`, `l` and `o`.
+    pub const GEOHASH: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+
+    /// Creates an alphabet from 32 symbols, the first of which encodes the value 0.
+    ///
+    /// Symbols must be unique, printable ASCII characters other than space. Letters
+    /// are decoded regardless of case unless the alphabet itself uses both cases of
+    /// the same letter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the symbols are invalid, which turns into a compilation
+    /// error when used to initialize a constant.
+    /// ```compile_fail
+    /// const ALPHABET: base32::Alphabet = base32::Alphabet::new(b"AACDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    /// ```
+    pub const fn new(symbols: &[u8; 32]) -> Alphabet {
+        let mut decode = [INVALID_BYTE; 256];
+
+        let mut i = 0;
+        while i < symbols.len() {
+            let symbol = symbols[i];
+            if !symbol.is_ascii_graphic() {
+                panic!("alphabet symbols must be printable ASCII characters");
+            }
+            if decode[symbol as usize] != INVALID_BYTE {
+                panic!("alphabet symbols must be unique");
+            }
+            decode[symbol as usize] = i as u8;
+            i += 1;
+        }
+
+        // Accept the other case of every letter as long as it isn't a symbol on its own
+        let mut i = 0;
+        while i < symbols.len() {
+            let symbol = symbols[i];
+            if symbol.is_ascii_alphabetic() && decode[(symbol ^ 0x20) as usize] == INVALID_BYTE {
+                decode[(symbol ^ 0x20) as usize] = i as u8;
+            }
+            i += 1;
+        }
+
+        Alphabet {
+            encode: *symbols,
+            decode,
+        }
+    }
+
+    /// Changes the case of every letter in the alphabet, which is the case they're encoded in.
+    /// Letters that are only decoded in one case follow along into the new case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alphabet uses both cases of the same letter.
+    pub const fn with_case(mut self, case: Case) -> Alphabet {
+        let mut i = 0;
+        while i < self.encode.len() {
+            let symbol = self.encode[i];
+            if symbol.is_ascii_alphabetic() {
+                let other = self.decode[(symbol ^ 0x20) as usize];
+                if other != INVALID_BYTE && self.encode[other as usize] == symbol ^ 0x20 {
+                    panic!("alphabet must not use both cases of a letter");
+                }
+
+                self.encode[i] = match case {
+                    Case::Upper => symbol.to_ascii_uppercase(),
+                    Case::Lower => symbol.to_ascii_lowercase(),
+                };
+            }
+            i += 1;
+        }
+
+        let mut letter = b'A';
+        while letter <= b'Z' {
+            let (from, to) = match case {
+                Case::Upper => (letter.to_ascii_lowercase(), letter),
+                Case::Lower => (letter, letter.to_ascii_lowercase()),
+            };
+            if self.decode[to as usize] == INVALID_BYTE {
+                self.decode[to as usize] = self.decode[from as usize];
+                self.decode[from as usize] = INVALID_BYTE;
+            }
+            letter += 1;
+        }
+
+        self
+    }
+
+    /// Decodes letters only in the case they're encoded in if `sensitive` is set, or
+    /// in either case otherwise. Letters that aren't symbols themselves but decode to
+    /// one, like `I` in [`Alphabet::CROCKFORD`], are kept in the case of the alphabet.
+    /// ```
+    /// use base32::Alphabet;
+    ///
+    /// let strict = Alphabet::STANDARD.with_case_sensitive_decoding(true);
+    /// assert_eq!(strict.value_of(b'B'), Some(1));
+    /// assert_eq!(strict.value_of(b'b'), None);
+    ///
+    /// let crockford = Alphabet::CROCKFORD.with_case_sensitive_decoding(true);
+    /// assert_eq!(crockford.value_of(b'I'), Some(1));
+    /// assert_eq!(crockford.value_of(b'i'), None);
+    /// ```
+    pub const fn with_case_sensitive_decoding(mut self, sensitive: bool) -> Alphabet {
+        let mut upper = true;
+        let mut i = 0;
+        while i < self.encode.len() {
+            if self.encode[i].is_ascii_alphabetic() {
+                upper = self.encode[i].is_ascii_uppercase();
+                break;
+            }
+            i += 1;
+        }
+
+        let mut letter = b'A';
+        while letter <= b'Z' {
+            let lower = letter.to_ascii_lowercase();
+            let (value, other) = (self.decode[letter as usize], self.decode[lower as usize]);
+
+            if sensitive && value != INVALID_BYTE && value == other {
+                let symbol = self.encode[value as usize];
+                let keep_upper = if symbol == letter || symbol == lower {
+                    symbol == letter
+                } else {
+                    upper
+                };
+                let rejected = if keep_upper { lower } else { letter };
+                self.decode[rejected as usize] = INVALID_BYTE;
+            } else if !sensitive && value == INVALID_BYTE {
+                self.decode[letter as usize] = other;
+            } else if !sensitive && other == INVALID_BYTE {
+                self.decode[lower as usize] = value;
+            }
+            letter += 1;
+        }
+
+        self
+    }
+
+    const fn from_tables(encode: &[u8; 32], decode: &[u8; 256]) -> Alphabet {
+        Alphabet {
+            encode: *encode,
+            decode: *decode,
+        }
+    }
+
+    /// Returns the symbols of the alphabet, ordered by the value they encode.
+    #[inline]
+    pub const fn symbols(&self) -> &[u8; 32] {
+        &self.encode
+    }
+
+    /// Returns the value that the symbol decodes to, or `None` if it isn't part of the alphabet.
+    /// ```
+    /// use base32::Alphabet;
+    ///
+    /// assert_eq!(Alphabet::CROCKFORD.value_of(b'o'), Some(0));
+    /// assert_eq!(Alphabet::CROCKFORD.value_of(b'U'), None);
+    /// ```
+    #[inline]
+    pub const fn value_of(&self, symbol: u8) -> Option<u8> {
+        match self.decode[symbol as usize] {
+            INVALID_BYTE => None,
+            value => Some(value),
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn encode_table(&self) -> &[u8; 32] {
+        &self.encode
+    }
+
+    #[inline]
+    pub(crate) const fn decode_table(&self) -> &[u8; 256] {
+        &self.decode
+    }
+}
+
+/// A table encoding two symbols of an [`Alphabet`] at a time, used by encodings set up with
+/// [`Encoding::with_pair_encode_table`](crate::Encoding::with_pair_encode_table).
+///
+/// At 2 KiB it would make every copy of an encoding expensive, so encodings only hold a
+/// `'static` reference to one built at compile time. The presets already come with one.
+/// ```
+/// use base32::{Alphabet, Encoding, PairEncodeTable};
+///
+/// const ALPHABET: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+/// const PAIRS: &PairEncodeTable = &PairEncodeTable::new(&ALPHABET);
+/// const ENCODING: Encoding = Encoding::new(ALPHABET, None).with_pair_encode_table(PAIRS);
+///
+/// assert_eq!(ENCODING.encode("foobar"), "dtrqysm1f8");
+/// ```
+pub struct PairEncodeTable {
+    encode: [u8; 32],
+    pairs: [[u8; 2]; 1024],
+}
+
+impl PairEncodeTable {
+    /// Builds the table for the alphabet, which maps every 10-bit value to the two symbols
+    /// encoding it.
+    pub const fn new(alphabet: &Alphabet) -> PairEncodeTable {
+        let encode = alphabet.encode;
+        let mut pairs = [[0; 2]; 1024];
+
+        let mut i = 0;
+        while i < pairs.len() {
+            pairs[i] = [encode[i >> 5], encode[i & 0b11111]];
+            i += 1;
+        }
+
+        PairEncodeTable { encode, pairs }
+    }
+
+    /// Returns whether the table encodes exactly the same symbols as the alphabet.
+    pub(crate) const fn matches(&self, alphabet: &Alphabet) -> bool {
+        let mut i = 0;
+        while i < self.encode.len() {
+            if self.encode[i] != alphabet.encode[i] {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    /// Returns the table mapping every 10-bit value to the two symbols encoding it.
+    #[inline]
+    pub(crate) const fn table(&self) -> &[[u8; 2]; 1024] {
+        &self.pairs
+    }
+}
+
+impl fmt::Debug for PairEncodeTable {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PairEncodeTable").finish_non_exhaustive()
+    }
+}
+
+/// A table decoding two symbols of an [`Alphabet`] at a time, used by encodings set up with
+/// [`Encoding::with_pair_decode_table`](crate::Encoding::with_pair_decode_table).
+///
+/// At 128 KiB it's too large to pass around by value, so encodings only hold a `'static`
+/// reference to one built at compile time. The presets already come with one.
+/// ```
+/// use base32::{Alphabet, Encoding, PairDecodeTable};
+///
+/// const ALPHABET: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+/// const PAIRS: &PairDecodeTable = &PairDecodeTable::new(&ALPHABET);
+/// const ENCODING: Encoding = Encoding::new(ALPHABET, None).with_pair_decode_table(PAIRS);
+///
+/// assert_eq!(ENCODING.decode("dtrqysm1f8").unwrap(), b"foobar");
+/// ```
+#[cfg(feature = "pair-decode")]
+pub struct PairDecodeTable {
+    decode: [u8; 256],
+    pairs: [u16; 65536],
+}
+
+#[cfg(feature = "pair-decode")]
+impl PairDecodeTable {
+    /// Builds the table for the alphabet, which maps every pair of symbols to the 10 bits
+    /// they decode to.
+    pub const fn new(alphabet: &Alphabet) -> PairDecodeTable {
+        let decode = alphabet.decode;
+        let mut pairs = [tables::INVALID_PAIR; 65536];
+
+        let mut i = 0;
+        while i < pairs.len() {
+            let (first, second) = (decode[i >> 8], decode[i & 0xFF]);
+            if first != INVALID_BYTE && second != INVALID_BYTE {
+                pairs[i] = (first as u16) << 5 | second as u16;
+            }
+            i += 1;
+        }
+
+        PairDecodeTable { decode, pairs }
+    }
+
+    /// Returns whether the table decodes exactly the same symbols as the alphabet.
+    pub(crate) const fn matches(&self, alphabet: &Alphabet) -> bool {
+        let mut i = 0;
+        while i < self.decode.len() {
+            if self.decode[i] != alphabet.decode[i] {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    /// Returns the table indexed by pairs of symbols, the first one in the upper byte,
+    /// holding [`INVALID_PAIR`](tables::INVALID_PAIR) if either symbol is invalid.
+    #[inline]
+    pub(crate) const fn table(&self) -> &[u16; 65536] {
+        &self.pairs
+    }
+}
+
+#[cfg(feature = "pair-decode")]
+impl fmt::Debug for PairDecodeTable {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PairDecodeTable").finish_non_exhaustive()
+    }
+}
+
+/// The case that the letters of an alphabet are encoded in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// Encodes letters in uppercase, like `MZXW6===`.
+    Upper,
+    /// Encodes letters in lowercase, like `mzxw6===`.
+    Lower,
+}
+
+impl fmt::Debug for Alphabet {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Symbols are guaranteed to be ASCII, so this never fails
+        let symbols = core::str::from_utf8(&self.encode).map_err(|_| fmt::Error)?;
+
+        fmt.debug_tuple("Alphabet").field(&symbols).finish()
+    }
+}
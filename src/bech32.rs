@@ -0,0 +1,351 @@
+//! Bech32 and Bech32m strings as described in
+//! [BIP-173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki) and
+//! [BIP-350](https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki).
+//!
+//! A Bech32 string is made up of a human-readable part, the separator `1`, data in the form of
+//! 5-bit values encoded with [`Alphabet::BECH32`] and a checksum of six symbols. Bytes are
+//! converted to values the same way as any other encoding of this crate without padding, which
+//! makes the strings compatible with those used for keys by Nostr and age, among others.
+//! ```
+//! use base32::bech32::{self, Variant};
+//!
+//! let encoded = bech32::encode("npub", b"foobar", Variant::Bech32).unwrap();
+//! assert_eq!(encoded, "npub1vehk7cnpwg06anxz");
+//!
+//! let (hrp, values, variant) = bech32::decode(&encoded).unwrap();
+//! assert_eq!((hrp.as_str(), variant), ("npub", Variant::Bech32));
+//! assert_eq!(bech32::to_bytes(&values).unwrap(), b"foobar");
+//! ```
+//!
+//! Data that doesn't make up whole bytes is left as values, like the witness version at the
+//! start of a segwit address.
+//! ```
+//! use base32::bech32::{self, Variant};
+//!
+//! let (hrp, values, variant) = bech32::decode("BC1SW50QGDZ25J").unwrap();
+//! assert_eq!((hrp.as_str(), values[0], variant), ("bc", 16, Variant::Bech32m));
+//! assert_eq!(bech32::to_bytes(&values[1..]).unwrap(), [0x75, 0x1e]);
+//!
+//! let encoded = bech32::encode_values(&hrp, &values, variant).unwrap();
+//! assert_eq!(encoded, "bc1sw50qgdz25j");
+//! ```
+use alloc::{string::String, vec::Vec};
+use core::{fmt, iter};
+#[cfg(any(feature = "std", test))]
+use std::error;
+
+use crate::{Alphabet, Encoding};
+
+/// The maximum length of a Bech32 string, including the human-readable part.
+pub const MAX_LEN: usize = 90;
+
+const CHECKSUM_LEN: usize = 6;
+const SEPARATOR: u8 = b'1';
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+// Converts between bytes and symbols, rejecting leftover bits that aren't zero
+const DATA: Encoding = Encoding::new(Alphabet::BECH32, None).with_canonical_decoding(true);
+
+/// The checksum algorithm of a Bech32 string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// The original checksum from BIP-173.
+    Bech32,
+    /// The improved checksum from BIP-350, which detects symbols inserted
+    /// or removed right before a trailing `p`.
+    Bech32m,
+}
+
+impl Variant {
+    const fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+/// Errors that can occur while encoding or decoding Bech32 strings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The string is longer than [`MAX_LEN`], or the data part is too short to hold
+    /// a checksum.
+    InvalidLength(usize),
+    /// The string contains both uppercase and lowercase letters.
+    MixedCase,
+    /// The string doesn't contain the separator `1`.
+    MissingSeparator,
+    /// The human-readable part is empty.
+    EmptyHrp,
+    /// An invalid byte was found at the given offset, either outside of the printable
+    /// ASCII range in the human-readable part or not a symbol in the data part.
+    InvalidByte(usize, u8),
+    /// The value at the given index doesn't fit into five bits.
+    InvalidValue(usize, u8),
+    /// The checksum didn't match. If changing a single symbol would make it match, its
+    /// offset is included, as that's most likely where the typo is.
+    ChecksumMismatch(Option<usize>),
+    /// The values have leftover bits that don't make up a whole byte but aren't zero, or
+    /// too many of them.
+    InvalidPadding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength(len) => write!(fmt, "invalid length {}", len),
+            Error::MixedCase => write!(fmt, "mixed case string"),
+            Error::MissingSeparator => write!(fmt, "missing separator"),
+            Error::EmptyHrp => write!(fmt, "empty human-readable part"),
+            Error::InvalidByte(index, byte) => {
+                write!(fmt, "invalid byte {:?} at offset {}", *byte as char, index)
+            }
+            Error::InvalidValue(index, value) => {
+                write!(fmt, "invalid value {} at index {}", value, index)
+            }
+            Error::ChecksumMismatch(Some(index)) => {
+                write!(fmt, "checksum mismatch, likely error at offset {}", index)
+            }
+            Error::ChecksumMismatch(None) => write!(fmt, "checksum mismatch"),
+            Error::InvalidPadding => write!(fmt, "invalid padding"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for Error {}
+
+/// Encodes the data along with the human-readable part and a checksum of the given variant.
+/// The result is always lowercase.
+/// ```
+/// use base32::bech32::{self, Error, Variant};
+///
+/// assert_eq!(bech32::encode("a", b"", Variant::Bech32m).unwrap(), "a1lqfn3a");
+/// assert_eq!(bech32::encode("a", &[0; 60], Variant::Bech32m), Err(Error::InvalidLength(104)));
+/// ```
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Error> {
+    let len = DATA.encoded_size(data.len()).unwrap_or(usize::MAX);
+    check_len(hrp, len)?;
+
+    Ok(encode_symbols(hrp, DATA.encode(data).as_bytes(), variant))
+}
+
+/// Encodes 5-bit values along with the human-readable part and a checksum of the given
+/// variant, for data that doesn't make up whole bytes. The result is always lowercase.
+/// ```
+/// use base32::bech32::{self, Error, Variant};
+///
+/// assert_eq!(bech32::encode_values("a", &[], Variant::Bech32m).unwrap(), "a1lqfn3a");
+/// assert_eq!(
+///     bech32::encode_values("a", &[32], Variant::Bech32m),
+///     Err(Error::InvalidValue(0, 32))
+/// );
+/// ```
+pub fn encode_values(hrp: &str, values: &[u8], variant: Variant) -> Result<String, Error> {
+    check_len(hrp, values.len())?;
+
+    Ok(encode_symbols(hrp, &symbols_of(values)?, variant))
+}
+
+/// Converts 5-bit values back into the bytes they were encoded from, which requires any
+/// leftover bits to be zero just like [`decode`](crate::Encoding::decode) with
+/// [canonical decoding](crate::Encoding::with_canonical_decoding).
+/// ```
+/// use base32::bech32::{self, Error};
+///
+/// assert_eq!(bech32::to_bytes(&[12, 25, 23, 22, 30]).unwrap(), b"foo");
+/// assert_eq!(bech32::to_bytes(&[12, 25, 23, 22, 31]), Err(Error::InvalidPadding));
+/// ```
+pub fn to_bytes(values: &[u8]) -> Result<Vec<u8>, Error> {
+    DATA.decode(symbols_of(values)?)
+        .map_err(|_| Error::InvalidPadding)
+}
+
+/// Decodes a Bech32 or Bech32m string into its lowercased human-readable part, the 5-bit
+/// values of its data and the variant of its checksum. Strings can be all uppercase or all
+/// lowercase. Use [`to_bytes`] to turn the values into bytes.
+/// ```
+/// use base32::bech32::{self, Error};
+///
+/// assert!(bech32::decode("A1LQFN3A").is_ok());
+/// assert_eq!(bech32::decode("a1lqfN3a"), Err(Error::MixedCase));
+/// assert_eq!(bech32::decode("a1lqfn4a"), Err(Error::ChecksumMismatch(Some(6))));
+/// ```
+pub fn decode(s: impl AsRef<[u8]>) -> Result<(String, Vec<u8>, Variant), Error> {
+    let s = s.as_ref();
+    if s.len() > MAX_LEN {
+        return Err(Error::InvalidLength(s.len()));
+    }
+
+    if let Some(i) = s.iter().position(|b| !(33..=126).contains(b)) {
+        return Err(Error::InvalidByte(i, s[i]));
+    }
+    if s.iter().any(u8::is_ascii_lowercase) && s.iter().any(u8::is_ascii_uppercase) {
+        return Err(Error::MixedCase);
+    }
+
+    let separator = match s.iter().rposition(|b| *b == SEPARATOR) {
+        Some(i) => i,
+        None => return Err(Error::MissingSeparator),
+    };
+    let (hrp, symbols) = (&s[..separator], &s[separator + 1..]);
+    if hrp.is_empty() {
+        return Err(Error::EmptyHrp);
+    }
+    if symbols.len() < CHECKSUM_LEN {
+        return Err(Error::InvalidLength(s.len()));
+    }
+
+    let mut values = Vec::with_capacity(symbols.len());
+    for (i, &symbol) in symbols.iter().enumerate() {
+        match Alphabet::BECH32.value_of(symbol) {
+            Some(value) => values.push(value),
+            None => return Err(Error::InvalidByte(separator + 1 + i, symbol)),
+        }
+    }
+
+    let hrp = hrp
+        .iter()
+        .map(|b| b.to_ascii_lowercase() as char)
+        .collect::<String>();
+    let residue = polymod(expand_hrp(hrp.as_bytes()).chain(values.iter().copied()));
+
+    let variant = match residue {
+        r if r == Variant::Bech32.constant() => Variant::Bech32,
+        r if r == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => {
+            let error = [Variant::Bech32, Variant::Bech32m]
+                .iter()
+                .find_map(|v| locate_error(residue ^ v.constant(), values.len()))
+                .map(|i| separator + 1 + i);
+            return Err(Error::ChecksumMismatch(error));
+        }
+    };
+
+    values.truncate(values.len() - CHECKSUM_LEN);
+
+    Ok((hrp, values, variant))
+}
+
+/// Checks the human-readable part and that the string stays within [`MAX_LEN`]
+/// with the given amount of data symbols.
+fn check_len(hrp: &str, symbols: usize) -> Result<(), Error> {
+    validate_hrp(hrp.as_bytes())?;
+
+    let len = (hrp.len() + 1)
+        .saturating_add(symbols)
+        .saturating_add(CHECKSUM_LEN);
+    if len > MAX_LEN {
+        return Err(Error::InvalidLength(len));
+    }
+
+    Ok(())
+}
+
+/// Builds the string from data that has already been converted to symbols.
+fn encode_symbols(hrp: &str, symbols: &[u8], variant: Variant) -> String {
+    let hrp = hrp.as_bytes();
+
+    let mut encoded = Vec::with_capacity(hrp.len() + 1 + symbols.len() + CHECKSUM_LEN);
+    encoded.extend(hrp.iter().map(u8::to_ascii_lowercase));
+    encoded.push(SEPARATOR);
+    encoded.extend_from_slice(symbols);
+
+    let values = symbols.iter().map(|b| value_of(*b));
+    let checksum = polymod(
+        expand_hrp(&encoded[..hrp.len()])
+            .chain(values)
+            .chain([0; CHECKSUM_LEN]),
+    ) ^ variant.constant();
+
+    for i in 0..CHECKSUM_LEN {
+        let value = (checksum >> (5 * (CHECKSUM_LEN - 1 - i))) & 0x1f;
+        encoded.push(Alphabet::BECH32.symbols()[value as usize]);
+    }
+
+    String::from_utf8(encoded).expect("Implementation error")
+}
+
+fn validate_hrp(hrp: &[u8]) -> Result<(), Error> {
+    if hrp.is_empty() {
+        return Err(Error::EmptyHrp);
+    }
+
+    match hrp.iter().position(|b| !(33..=126).contains(b)) {
+        Some(i) => Err(Error::InvalidByte(i, hrp[i])),
+        None => Ok(()),
+    }
+}
+
+fn symbols_of(values: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut symbols = Vec::with_capacity(values.len());
+    for (i, &value) in values.iter().enumerate() {
+        match Alphabet::BECH32.symbols().get(value as usize) {
+            Some(&symbol) => symbols.push(symbol),
+            None => return Err(Error::InvalidValue(i, value)),
+        }
+    }
+
+    Ok(symbols)
+}
+
+fn value_of(symbol: u8) -> u8 {
+    Alphabet::BECH32
+        .value_of(symbol)
+        .expect("Symbol must be part of the alphabet")
+}
+
+/// Splits every byte of the human-readable part into its upper and lower bits, so
+/// that all of them are covered by the checksum.
+fn expand_hrp(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|b| b >> 5)
+        .chain(iter::once(0))
+        .chain(hrp.iter().map(|b| b & 0x1f))
+}
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    values.into_iter().fold(1, step)
+}
+
+fn step(checksum: u32, value: u8) -> u32 {
+    let top = checksum >> 25;
+    let mut checksum = (checksum & 0x1ffffff) << 5 ^ value as u32;
+
+    for (i, generator) in GENERATOR.iter().enumerate() {
+        if (top >> i) & 1 == 1 {
+            checksum ^= generator;
+        }
+    }
+
+    checksum
+}
+
+/// Finds the offset of the only symbol among `len` that, when changed, turns the checksum
+/// residue into zero.
+///
+/// As the checksum is linear, changing the symbol `j` places from the end by `e` changes the
+/// residue by the checksum of `e` followed by `j` zeros, which are built up one place at a time.
+fn locate_error(residue: u32, len: usize) -> Option<usize> {
+    let mut changes = [0; 32];
+    for (e, change) in changes.iter_mut().enumerate() {
+        *change = e as u32;
+    }
+
+    let mut found = None;
+    for j in 0..len {
+        if changes[1..].contains(&residue) {
+            if found.is_some() {
+                return None;
+            }
+            found = Some(len - 1 - j);
+        }
+
+        for change in &mut changes {
+            *change = step(*change, 0);
+        }
+    }
+
+    found
+}
@@ -0,0 +1,456 @@
+//! A drop-in replacement for the coreutils `base32` command, which also supports the
+//! other alphabets of the crate. Options, messages and exit codes follow coreutils.
+#![deny(
+    missing_debug_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_results,
+    variant_size_differences,
+    warnings,
+    rust_2018_idioms
+)]
+#![forbid(unsafe_code)]
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::process;
+
+use base32::write::EncoderWriter;
+use base32::{Encoding, LineEnding, PaddingMode};
+
+const NAME: &str = "base32";
+const DEFAULT_WRAP: usize = 76;
+const BUF_LEN: usize = 8 * 1024;
+const CHUNK_LEN: usize = 8;
+
+// coreutils only decodes its own alphabets in uppercase, so lowercase letters are
+// skipped along with other garbage
+static BASE32: Encoding = base32::STANDARD.with_case_sensitive_decoding(true);
+static BASE32HEX: Encoding = base32::EXTENDED_HEX.with_case_sensitive_decoding(true);
+
+const USAGE: &str = "\
+Usage: base32 [OPTION]... [FILE]
+Base32 encode or decode FILE, or standard input, to standard output.
+
+With no FILE, or when FILE is -, read standard input.
+
+Mandatory arguments to long options are mandatory for short options too.
+  -d, --decode          decode data
+  -i, --ignore-garbage  when decoding, ignore non-alphabet characters
+  -w, --wrap=COLS       wrap encoded lines after COLS character (default 76).
+                          Use 0 to disable line wrapping
+
+      --base32          use the RFC 4648 alphabet (default)
+      --base32hex       use the extended hex alphabet of RFC 4648
+      --crockford       use Crockford's alphabet, without padding
+      --zbase32         use the z-base-32 alphabet, without padding
+
+      --help        display this help and exit
+      --version     output version information and exit
+
+When decoding, the input may contain newlines in addition to the bytes of
+the chosen alphabet.  Use --ignore-garbage to attempt to recover
+from any other non-alphabet bytes in the encoded stream.
+";
+
+/// Long options along with whether they take an argument.
+const LONG_OPTIONS: [(&str, bool); 9] = [
+    ("decode", false),
+    ("ignore-garbage", false),
+    ("wrap", true),
+    ("base32", false),
+    ("base32hex", false),
+    ("crockford", false),
+    ("zbase32", false),
+    ("help", false),
+    ("version", false),
+];
+
+#[derive(Debug)]
+struct Options {
+    decode: bool,
+    ignore_garbage: bool,
+    wrap: usize,
+    encoding: &'static Encoding,
+    file: Option<OsString>,
+}
+
+/// Why the command line couldn't be parsed, printed along with a hint to use `--help`.
+#[derive(Debug)]
+enum UsageError {
+    InvalidOption(char),
+    UnrecognizedOption(String),
+    AmbiguousOption(String, Vec<&'static str>),
+    MissingShortArgument(char),
+    MissingLongArgument(&'static str),
+    UnexpectedArgument(&'static str),
+    ExtraOperand(OsString),
+}
+
+enum Action {
+    Run(Options),
+    Help,
+    Version,
+}
+
+fn main() {
+    let action = match parse_args(std::env::args_os().skip(1)) {
+        Ok(action) => action,
+        Err(e) => fail(&e),
+    };
+
+    let options = match action {
+        Action::Run(options) => options,
+        Action::Help => {
+            print!("{}", USAGE);
+            return;
+        }
+        Action::Version => {
+            println!("{} {}", NAME, env!("CARGO_PKG_VERSION"));
+            return;
+        }
+    };
+
+    if let Err(e) = run(&options) {
+        fail(&e);
+    }
+}
+
+/// An error that ends the command with exit code 1.
+#[derive(Debug)]
+enum Failure {
+    Usage(UsageError),
+    InvalidWrap(String),
+    Open(OsString, io::Error),
+    Read(io::Error),
+    Write(io::Error),
+    InvalidInput,
+}
+
+fn fail(failure: &Failure) -> ! {
+    match failure {
+        Failure::Usage(e) => print_usage_error(e),
+        Failure::InvalidWrap(wrap) => eprintln!("{}: invalid wrap size: '{}'", NAME, wrap),
+        Failure::Open(file, e) => {
+            eprintln!("{}: {}: {}", NAME, file.to_string_lossy(), os_message(e))
+        }
+        Failure::Read(e) => eprintln!("{}: read error: {}", NAME, os_message(e)),
+        Failure::Write(e) => eprintln!("{}: write error: {}", NAME, os_message(e)),
+        Failure::InvalidInput => eprintln!("{}: invalid input", NAME),
+    }
+
+    process::exit(1);
+}
+
+fn print_usage_error(error: &UsageError) {
+    match error {
+        UsageError::InvalidOption(c) => eprintln!("{}: invalid option -- '{}'", NAME, c),
+        UsageError::UnrecognizedOption(o) => eprintln!("{}: unrecognized option '{}'", NAME, o),
+        UsageError::AmbiguousOption(o, candidates) => {
+            let candidates = candidates
+                .iter()
+                .map(|c| format!("'--{}'", c))
+                .collect::<Vec<_>>()
+                .join(" ");
+            eprintln!(
+                "{}: option '{}' is ambiguous; possibilities: {}",
+                NAME, o, candidates
+            );
+        }
+        UsageError::MissingShortArgument(c) => {
+            eprintln!("{}: option requires an argument -- '{}'", NAME, c)
+        }
+        UsageError::MissingLongArgument(o) => {
+            eprintln!("{}: option '--{}' requires an argument", NAME, o)
+        }
+        UsageError::UnexpectedArgument(o) => {
+            eprintln!("{}: option '--{}' doesn't allow an argument", NAME, o)
+        }
+        UsageError::ExtraOperand(o) => {
+            eprintln!("{}: extra operand '{}'", NAME, o.to_string_lossy())
+        }
+    }
+    eprintln!("Try '{} --help' for more information.", NAME);
+}
+
+/// Strips the `(os error N)` suffix that the standard library adds to system errors.
+fn os_message(error: &io::Error) -> String {
+    let message = error.to_string();
+    match message.rfind(" (os error ") {
+        Some(i) if error.raw_os_error().is_some() => message[..i].to_string(),
+        _ => message,
+    }
+}
+
+/// Parses the arguments like `getopt_long` does, allowing options and the file to be mixed,
+/// short options to be grouped and long options to be abbreviated.
+fn parse_args(args: impl Iterator<Item = OsString>) -> Result<Action, Failure> {
+    let mut decode = false;
+    let mut ignore_garbage = false;
+    let mut wrap = None;
+    let mut encoding = &BASE32;
+    let mut operands = Vec::new();
+
+    let mut args = args.peekable();
+    let mut only_operands = false;
+
+    while let Some(arg) = args.next() {
+        let option = match arg.to_str() {
+            Some(s) if !only_operands && s.len() > 1 && s.starts_with('-') => s.to_string(),
+            _ => {
+                operands.push(arg);
+                continue;
+            }
+        };
+
+        if option == "--" {
+            only_operands = true;
+            continue;
+        }
+
+        if let Some(long) = option.strip_prefix("--") {
+            let (name, value) = match long.find('=') {
+                Some(i) => (&long[..i], Some(long[i + 1..].to_string())),
+                None => (long, None),
+            };
+            let (name, takes_argument) = find_long_option(name, &option)?;
+
+            let value = match (takes_argument, value) {
+                (true, Some(value)) => Some(value),
+                (true, None) => match args.next() {
+                    Some(value) => Some(value.to_string_lossy().into_owned()),
+                    None => return Err(Failure::Usage(UsageError::MissingLongArgument(name))),
+                },
+                (false, Some(_)) => {
+                    return Err(Failure::Usage(UsageError::UnexpectedArgument(name)))
+                }
+                (false, None) => None,
+            };
+
+            match name {
+                "decode" => decode = true,
+                "ignore-garbage" => ignore_garbage = true,
+                "wrap" => wrap = value,
+                "base32" => encoding = &BASE32,
+                "base32hex" => encoding = &BASE32HEX,
+                "crockford" => encoding = &base32::CROCKFORD,
+                "zbase32" => encoding = &base32::ZBASE32,
+                "help" => return Ok(Action::Help),
+                "version" => return Ok(Action::Version),
+                _ => unreachable!("Unhandled long option: {}", name),
+            }
+            continue;
+        }
+
+        for (i, c) in option.char_indices().skip(1) {
+            match c {
+                'd' => decode = true,
+                'i' => ignore_garbage = true,
+                'w' => {
+                    let rest = &option[i + 1..];
+                    wrap = if !rest.is_empty() {
+                        Some(rest.to_string())
+                    } else {
+                        match args.next() {
+                            Some(value) => Some(value.to_string_lossy().into_owned()),
+                            None => {
+                                return Err(Failure::Usage(UsageError::MissingShortArgument(c)))
+                            }
+                        }
+                    };
+                    break;
+                }
+                _ => return Err(Failure::Usage(UsageError::InvalidOption(c))),
+            }
+        }
+    }
+
+    let wrap = match wrap {
+        Some(wrap) => wrap.parse().map_err(|_| Failure::InvalidWrap(wrap))?,
+        None => DEFAULT_WRAP,
+    };
+
+    let mut operands = operands.into_iter();
+    let file = operands.next().filter(|file| file != "-");
+    if let Some(extra) = operands.next() {
+        return Err(Failure::Usage(UsageError::ExtraOperand(extra)));
+    }
+
+    Ok(Action::Run(Options {
+        decode,
+        ignore_garbage,
+        wrap,
+        encoding,
+        file,
+    }))
+}
+
+/// Finds the long option with the given name, or the only one it's an abbreviation of.
+fn find_long_option(name: &str, option: &str) -> Result<(&'static str, bool), Failure> {
+    if let Some(&exact) = LONG_OPTIONS.iter().find(|(long, _)| *long == name) {
+        return Ok(exact);
+    }
+
+    let candidates = LONG_OPTIONS
+        .iter()
+        .filter(|(long, _)| long.starts_with(name))
+        .collect::<Vec<_>>();
+
+    match candidates[..] {
+        [&only] => Ok(only),
+        [] => Err(Failure::Usage(UsageError::UnrecognizedOption(
+            option.to_string(),
+        ))),
+        _ => Err(Failure::Usage(UsageError::AmbiguousOption(
+            format!("--{}", name),
+            candidates.iter().map(|(long, _)| *long).collect(),
+        ))),
+    }
+}
+
+fn run(options: &Options) -> Result<(), Failure> {
+    let stdin = io::stdin();
+    let input: Box<dyn Read> = match &options.file {
+        Some(file) => Box::new(BufReader::new(
+            File::open(file).map_err(|e| Failure::Open(file.clone(), e))?,
+        )),
+        None => Box::new(stdin.lock()),
+    };
+
+    let stdout = io::stdout();
+    let output = BufWriter::new(stdout.lock());
+
+    if options.decode {
+        decode(options, input, output)
+    } else {
+        encode(options, input, output)
+    }
+}
+
+fn encode(options: &Options, mut input: impl Read, output: impl Write) -> Result<(), Failure> {
+    let encoding = match options.wrap {
+        0 => *options.encoding,
+        wrap => options.encoding.with_line_wrap(wrap, LineEnding::Lf),
+    };
+
+    let mut writer = EncoderWriter::new(output, encoding);
+    let mut buf = [0; BUF_LEN];
+    let mut empty = true;
+
+    loop {
+        let len = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(Failure::Read(e)),
+        };
+        empty = false;
+
+        writer.write_all(&buf[..len]).map_err(Failure::Write)?;
+    }
+
+    let mut output = writer.finish().map_err(Failure::Write)?;
+    if options.wrap > 0 && !empty {
+        output.write_all(b"\n").map_err(Failure::Write)?;
+    }
+
+    output.flush().map_err(Failure::Write)
+}
+
+fn decode(options: &Options, mut input: impl Read, mut output: impl Write) -> Result<(), Failure> {
+    let encoding = options.encoding.with_padding_mode(PaddingMode::Required);
+    let alphabet = *encoding.alphabet();
+
+    // newlines are always skipped, and so is anything that isn't
+    // a symbol or padding when ignoring garbage
+    let mut skipped = [false; 256];
+    for (byte, skipped) in (0..=255).zip(skipped.iter_mut()) {
+        *skipped = byte == b'\n'
+            || options.ignore_garbage
+                && alphabet.value_of(byte).is_none()
+                && Some(byte) != encoding.padding();
+    }
+
+    let mut buf = [0; BUF_LEN];
+    let mut symbols = Vec::with_capacity(BUF_LEN + CHUNK_LEN);
+    let mut decoded = vec![0; BUF_LEN / CHUNK_LEN * 5 + 5];
+
+    let result = loop {
+        let len = match input.read(&mut buf) {
+            Ok(len) => len,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => break Err(Failure::Read(e)),
+        };
+
+        symbols.extend(buf[..len].iter().filter(|b| !skipped[**b as usize]));
+
+        // unpadded alphabets may end in a partial chunk, which is left for EOF
+        let end = match len {
+            0 => symbols.len(),
+            _ => symbols.len() / CHUNK_LEN * CHUNK_LEN,
+        };
+        let (written, valid) = match decode_chunks(&encoding, &mut decoded, &symbols[..end]) {
+            Ok(written) => (written, true),
+            Err(written) => (written, false),
+        };
+        output
+            .write_all(&decoded[..written])
+            .map_err(Failure::Write)?;
+
+        if !valid {
+            break Err(Failure::InvalidInput);
+        } else if len == 0 {
+            break Ok(());
+        }
+
+        drop(symbols.drain(..end));
+    };
+
+    // whatever was decoded before an error is still written, like coreutils does
+    output.flush().map_err(Failure::Write)?;
+
+    result
+}
+
+/// Decodes the symbols one chunk at a time, each of which may end in padding like
+/// coreutils allows. If a chunk is invalid, the error holds how many bytes were
+/// decoded from the chunks in front of it.
+fn decode_chunks(encoding: &Encoding, output: &mut [u8], symbols: &[u8]) -> Result<usize, usize> {
+    // padding hardly ever shows up anywhere but at the end, so try everything at once first
+    if let Ok(written) = encoding.decode_to_slice(output, symbols) {
+        return Ok(written);
+    }
+
+    let mut written = 0;
+    for chunk in symbols.chunks(CHUNK_LEN) {
+        match encoding.decode_to_slice(&mut output[written..], chunk) {
+            Ok(len) => written += len,
+            Err(_) if chunk.len() == CHUNK_LEN => {
+                return Err(written + decode_prefix(encoding, &mut output[written..], chunk))
+            }
+            Err(_) => return Err(written),
+        }
+    }
+
+    Ok(written)
+}
+
+/// Decodes the whole bytes made up by the valid symbols at the start of an invalid
+/// chunk, which coreutils writes out before giving up.
+fn decode_prefix(encoding: &Encoding, output: &mut [u8], chunk: &[u8]) -> usize {
+    let alphabet = *encoding.alphabet();
+    let valid = chunk
+        .iter()
+        .take_while(|b| alphabet.value_of(**b).is_some())
+        .count();
+
+    // the fewest symbols that hold those bytes
+    let len = (valid * 5 / 8 * 8 + 4) / 5;
+
+    Encoding::new(alphabet, None)
+        .decode_to_slice(output, &chunk[..len])
+        .expect("Symbols must be valid")
+}
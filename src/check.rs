@@ -0,0 +1,182 @@
+#[cfg(any(feature = "alloc", feature = "std", test))]
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::tables::{CHECK_SYMBOLS, INVALID_BYTE};
+use crate::{Alphabet, DecodeError, Encoding};
+
+impl Encoding {
+    /// Encodes arbitrary input bytes like [`encode`](Encoding::encode), followed
+    /// by a check symbol as described in Crockford's specification.
+    ///
+    /// The check symbol is the value of the encoded symbols, read as a big-endian
+    /// number in base 32, modulo 37. It is always taken from Crockford's set of check
+    /// symbols, which is the Crockford alphabet followed by `*`, `~`, `$`, `=` and `U`.
+    /// ```
+    /// assert_eq!(base32::CROCKFORD.encode_with_check("foobar"), "CSQPYRK1E8R");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding doesn't use the Crockford alphabet, or if its padding byte or
+    /// any of its ignored bytes are check symbols.
+    #[cfg(any(feature = "alloc", feature = "std", test))]
+    #[inline]
+    pub fn encode_with_check(&self, data: impl AsRef<[u8]>) -> String {
+        let data = data.as_ref();
+        let encoded_size = self
+            .encoded_size(data.len())
+            .and_then(|n| n.checked_add(1))
+            .expect("Overflowed while calculating encoded size of input");
+
+        let mut buf = vec![0; encoded_size];
+        let written = self.encode_with_check_to_slice(&mut buf, data);
+
+        debug_assert_eq!(written, buf.len());
+
+        String::from_utf8(buf).expect("Implementation error")
+    }
+
+    /// Takes a slice of arbitrary bytes and encodes it according to the configuration
+    /// followed by a check symbol, writing the resulting data into the output slice.
+    ///
+    /// The output slice needs room for one more byte than [`encoded_size`](Encoding::encoded_size)
+    /// reports for the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`encode_with_check`](Encoding::encode_with_check).
+    pub fn encode_with_check_to_slice(&self, output: &mut [u8], input: &[u8]) -> usize {
+        self.assert_check_symbols();
+
+        let written = self.encode_to_slice(output, input);
+
+        let symbol = CHECK_SYMBOLS[self.checksum(&output[..written]) as usize];
+
+        // the check symbol follows the case of the encoded data
+        output[written] = if self.alpha.symbols().iter().any(u8::is_ascii_lowercase) {
+            symbol.to_ascii_lowercase()
+        } else {
+            symbol
+        };
+
+        written + 1
+    }
+
+    /// Decodes data produced by [`encode_with_check`](Encoding::encode_with_check),
+    /// verifying the trailing check symbol against the decoded bytes.
+    /// ```
+    /// assert!(base32::CROCKFORD.decode_with_check("CSQPYRK1E8R").is_ok());
+    /// assert!(base32::CROCKFORD.decode_with_check("CSQPYRK1E8S").is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`encode_with_check`](Encoding::encode_with_check).
+    #[cfg(any(feature = "alloc", feature = "std", test))]
+    #[inline]
+    pub fn decode_with_check(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
+        let data = data.as_ref();
+
+        let len = match self.decoded_size(data.len()) {
+            Some(x) => x,
+            None => return Err(DecodeError::InvalidInputLength),
+        };
+
+        let mut buf = vec![0; len];
+        let written = self.decode_with_check_to_slice(&mut buf, data)?;
+
+        buf.truncate(written);
+
+        Ok(buf)
+    }
+
+    /// Takes a slice of encoded data ending in a check symbol and decodes it into
+    /// the output slice according to the configuration, returning
+    /// [`DecodeError::ChecksumMismatch`] if the check symbol doesn't match.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`encode_with_check`](Encoding::encode_with_check).
+    pub fn decode_with_check_to_slice(
+        &self,
+        output: &mut [u8],
+        input: &[u8],
+    ) -> Result<usize, DecodeError> {
+        self.assert_check_symbols();
+
+        // ignored bytes may follow the check symbol
+        let end = input
+            .iter()
+            .rposition(|b| !self.ignore.contains(*b))
+            .map_or(0, |i| i + 1);
+
+        let (&symbol, input) = match input[..end].split_last() {
+            Some(x) => x,
+            None => return Err(DecodeError::InvalidInputLength),
+        };
+
+        let expected = match self.decode_check_symbol(symbol) {
+            Some(x) => x,
+            None => return Err(DecodeError::InvalidByte(input.len(), symbol)),
+        };
+
+        let written = self.decode_to_slice(output, input)?;
+
+        if self.checksum(input) != expected {
+            return Err(DecodeError::ChecksumMismatch);
+        }
+
+        Ok(written)
+    }
+
+    /// Check symbols are only defined for the Crockford alphabet, and the ones past its
+    /// symbols would be mistaken for padding or skipped if the encoding used them.
+    fn assert_check_symbols(&self) {
+        if !self
+            .alpha
+            .symbols()
+            .eq_ignore_ascii_case(Alphabet::CROCKFORD.symbols())
+        {
+            panic!("check symbols require the Crockford alphabet");
+        }
+
+        for &symbol in CHECK_SYMBOLS[32..].iter().chain(b"u") {
+            if self.pad == Some(symbol) || self.ignore.contains(symbol) {
+                panic!("check symbols must not be the padding byte or ignored");
+            }
+        }
+    }
+
+    /// `U` isn't a symbol of the alphabet, so it's accepted in whichever case the alphabet
+    /// decodes its letters in.
+    fn decode_check_symbol(&self, symbol: u8) -> Option<u8> {
+        let table = self.alpha.decode_table();
+
+        match symbol {
+            b'*' => Some(32),
+            b'~' => Some(33),
+            b'$' => Some(34),
+            b'=' => Some(35),
+            b'U' if table[b'A' as usize] != INVALID_BYTE => Some(36),
+            b'u' if table[b'a' as usize] != INVALID_BYTE => Some(36),
+            _ => match table[symbol as usize] {
+                INVALID_BYTE => None,
+                value => Some(value),
+            },
+        }
+    }
+
+    /// Computes the check value of encoded data, skipping anything that isn't a symbol
+    /// such as padding and ignored bytes.
+    fn checksum(&self, encoded: &[u8]) -> u8 {
+        let table = self.alpha.decode_table();
+
+        encoded
+            .iter()
+            .map(|b| table[*b as usize])
+            .filter(|value| *value != INVALID_BYTE)
+            .fold(0, |acc: u32, value| {
+                (acc << 5 | value as u32) % CHECK_SYMBOLS.len() as u32
+            }) as u8
+    }
+}
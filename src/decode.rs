@@ -5,6 +5,8 @@ use core::fmt;
 use std::error;
 
 use crate::tables::INVALID_BYTE;
+#[cfg(feature = "pair-decode")]
+use crate::tables::INVALID_PAIR;
 use crate::Encoding;
 
 /// Decodes data encoded using the standard base32 format
@@ -21,7 +23,8 @@ pub fn decode(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecodeError> {
 }
 
 /// Errors that can occur while decoding
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DecodeError {
     /// Returned if a byte that isn't present in the decoding table is encountered,
     /// it contains the index at which the offending byte was found as well as its
@@ -29,6 +32,23 @@ pub enum DecodeError {
     InvalidByte(usize, u8),
     /// Returned if the input is of a length that could never produce well formed output.
     InvalidInputLength,
+    /// Returned if the check symbol doesn't match the decoded data.
+    ChecksumMismatch,
+    /// Returned by canonical decoding if the last symbol has non-zero bits that don't make up a
+    /// whole byte, meaning that other symbols would decode to the same data. It contains the
+    /// index at which the offending symbol was found as well as its value.
+    InvalidLastSymbol(usize, u8),
+    /// Returned if the padding at the end of the input is malformed, either because it doesn't
+    /// make up a whole chunk when required, runs past the end of the chunk or because there
+    /// are symbols missing in front of it. It contains the index at which the padding starts.
+    InvalidPadding(usize),
+    /// Returned if padding is required but the input doesn't make up a whole chunk.
+    MissingPadding,
+    /// Returned if padding is forbidden but found anyway, it contains the index
+    /// at which the padding starts.
+    UnexpectedPadding(usize),
+    /// Returned if the output slice is too small to hold the decoded data.
+    OutputBufferTooSmall,
 }
 
 impl fmt::Display for DecodeError {
@@ -38,6 +58,14 @@ impl fmt::Display for DecodeError {
                 write!(fmt, "invalid input byte at index {}: {}", i, b)
             }
             DecodeError::InvalidInputLength => write!(fmt, "invalid input length"),
+            DecodeError::ChecksumMismatch => write!(fmt, "checksum mismatch"),
+            DecodeError::InvalidLastSymbol(i, b) => {
+                write!(fmt, "invalid last symbol at index {}: {}", i, b)
+            }
+            DecodeError::InvalidPadding(i) => write!(fmt, "invalid padding at index {}", i),
+            DecodeError::MissingPadding => write!(fmt, "missing padding"),
+            DecodeError::UnexpectedPadding(i) => write!(fmt, "unexpected padding at index {}", i),
+            DecodeError::OutputBufferTooSmall => write!(fmt, "output buffer too small"),
         }
     }
 }
@@ -45,6 +73,19 @@ impl fmt::Display for DecodeError {
 #[cfg(any(feature = "std", test))]
 impl error::Error for DecodeError {}
 
+/// Controls how padding at the end of the input is treated while decoding. This only
+/// applies to encodings that have a padding byte.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PaddingMode {
+    /// The final chunk must be padded to a whole chunk as described in RFC 4648.
+    Required,
+    /// Accepts input that is either unpadded or padded, even if the padding stops short of
+    /// the end of the chunk.
+    Optional,
+    /// Rejects any padding.
+    Forbidden,
+}
+
 const OUTPUT_BLOCK_OVERHEAD: usize = 3;
 
 const INPUT_CHUNK_LEN: usize = 8;
@@ -75,6 +116,43 @@ impl Encoding {
         Ok(buf)
     }
 
+    /// Decodes valid base32-encoded data according to the configuration, appending the
+    /// resulting raw bytes to the vector and returning how many were appended. The vector
+    /// is grown at most once, and left as it was if decoding fails.
+    /// ```
+    /// let mut buf = b"foo".to_vec();
+    /// base32::STANDARD.decode_to_vec("MJQXE===", &mut buf).unwrap();
+    ///
+    /// assert_eq!(buf, b"foobar");
+    /// ```
+    #[cfg(any(feature = "alloc", feature = "std", test))]
+    pub fn decode_to_vec(
+        &self,
+        data: impl AsRef<[u8]>,
+        output: &mut Vec<u8>,
+    ) -> Result<usize, DecodeError> {
+        let data = data.as_ref();
+
+        let len = match self.decoded_size(data.len()) {
+            Some(x) => x,
+            None => return Err(DecodeError::InvalidInputLength),
+        };
+
+        let start = output.len();
+        output.resize(start + len, 0);
+
+        match self.decode_to_slice(&mut output[start..], data) {
+            Ok(written) => {
+                output.truncate(start + written);
+                Ok(written)
+            }
+            Err(e) => {
+                output.truncate(start);
+                Err(e)
+            }
+        }
+    }
+
     /// Returns an estimate of how many bytes would be required to store the decoded form
     /// of the given amount of encoded bytes. It will sometimes overestimate how many are
     /// needed, but never underestimate.
@@ -83,63 +161,148 @@ impl Encoding {
         bytes.checked_mul(5).map(|n| n / 8)
     }
 
-    /// Takes a slice of encoded data and decodes it into
-    /// the output slice according to the configuration.
+    /// Same as [`decode_to_slice`](Encoding::decode_to_slice), except that it returns
+    /// [`DecodeError::OutputBufferTooSmall`] instead of panicking if the output slice
+    /// is smaller than [`decoded_len`](Encoding::decoded_len) of the input.
+    /// ```
+    /// let mut buf = [0; 6];
+    /// assert_eq!(base32::STANDARD.try_decode_to_slice(&mut buf, b"MZXW6YTBOI======"), Ok(6));
+    /// assert_eq!(&buf, b"foobar");
+    ///
+    /// assert!(base32::STANDARD.try_decode_to_slice(&mut buf[..5], b"MZXW6YTBOI======").is_err());
+    /// ```
+    pub fn try_decode_to_slice(
+        &self,
+        output: &mut [u8],
+        input: &[u8],
+    ) -> Result<usize, DecodeError> {
+        if self.decoded_len(input)? > output.len() {
+            return Err(DecodeError::OutputBufferTooSmall);
+        }
+
+        self.decode_to_slice(output, input)
+    }
+
+    /// Calculates exactly how many bytes the encoded data decodes to, by leaving out any
+    /// padding and ignored bytes and looking at how many symbols the final chunk has.
+    /// The symbols themselves aren't validated, so decoding may still fail.
+    /// ```
+    /// assert_eq!(base32::STANDARD.decoded_len(b"MZXW6YTBOI======"), Ok(6));
+    /// assert_eq!(base32::STANDARD.decoded_len(b"MZXW6YTBOI"), Ok(6));
+    /// assert!(base32::STANDARD.decoded_len(b"MZXW6YTBO").is_err());
+    /// ```
+    pub fn decoded_len(&self, input: &[u8]) -> Result<usize, DecodeError> {
+        let ignored = &self.ignore;
+        let symbols = if ignored.is_empty() {
+            input.len()
+        } else {
+            input.iter().filter(|b| !ignored.contains(**b)).count()
+        };
+        let padding = self.trailing_padding(input.iter().filter(|b| !ignored.contains(**b)));
+
+        let quintets = symbols - padding;
+        match quintets % INPUT_CHUNK_LEN {
+            // invalid unpadded input lengths ...
+            1 | 3 | 6 => Err(self.length_error(input)),
+            n => Ok(quintets / INPUT_CHUNK_LEN * OUTPUT_CHUNK_LEN + n * 5 / 8),
+        }
+    }
+
+    /// Returns the error for input with an amount of symbols that can't be decoded. If the
+    /// input ends in padding, the padding is to blame, which makes the error the same no
+    /// matter how many padding bytes there are.
+    fn length_error(&self, input: &[u8]) -> DecodeError {
+        let pad = match self.pad {
+            Some(pad) => pad,
+            None => return DecodeError::InvalidInputLength,
+        };
+
+        let mut start = None;
+        for (i, b) in input.iter().enumerate().rev() {
+            if *b == pad {
+                start = Some(i);
+            } else if !self.ignore.contains(*b) {
+                break;
+            }
+        }
+
+        match start {
+            Some(i) if self.padding == PaddingMode::Forbidden => DecodeError::UnexpectedPadding(i),
+            Some(i) => DecodeError::InvalidPadding(i),
+            None => DecodeError::InvalidInputLength,
+        }
+    }
+
+    /// Returns how many padding bytes the symbols end in.
+    fn trailing_padding<'a>(&self, symbols: impl DoubleEndedIterator<Item = &'a u8>) -> usize {
+        symbols.rev().take_while(|b| Some(**b) == self.pad).count()
+    }
+
+    /// Returns the error for a padding byte found by the fast paths, which is the same as
+    /// for padding at the end of the input where padding is forbidden.
+    fn padding_error(&self, error: DecodeError) -> DecodeError {
+        match error {
+            DecodeError::InvalidByte(i, b)
+                if Some(b) == self.pad && self.padding == PaddingMode::Forbidden =>
+            {
+                DecodeError::UnexpectedPadding(i)
+            }
+            e => e,
+        }
+    }
+
+    /// Takes a slice of encoded data and decodes it into the output slice according to
+    /// the configuration. An output slice of [`decoded_len`](Encoding::decoded_len)
+    /// bytes is always large enough.
     pub fn decode_to_slice(&self, output: &mut [u8], input: &[u8]) -> Result<usize, DecodeError> {
+        if !self.ignore.is_empty() {
+            return self.decode_ignoring_to_slice(output, input);
+        }
+
+        // trailing padding is left to the tail, however long it is
+        let end = input.len() - self.trailing_padding(input.iter());
         let (remainder, mut chunks) = (
-            input.len() % INPUT_CHUNK_LEN,
-            input
-                .len()
-                .checked_add(INPUT_CHUNK_LEN - 1)
+            end % INPUT_CHUNK_LEN,
+            end.checked_add(INPUT_CHUNK_LEN - 1)
                 .expect("Calculating amount of input chunks overflowed")
                 / INPUT_CHUNK_LEN,
         );
         let trailing_bytes_to_skip = match remainder {
-            // invalid unpadded input lengths ...
-            1 | 3 | 6 => return Err(DecodeError::InvalidInputLength),
+            // invalid unpadded input lengths, padded ones are left to the tail so that
+            // invalid bytes are found first ...
+            1 | 3 | 6 if end == input.len() => return Err(self.length_error(input)),
             // if any other length is present, we must ignore
             // the final block plus any bytes present in the
             // remainder
             _ => INPUT_CHUNK_LEN + remainder,
         };
 
-        let mut output_index = 0;
-        let mut input_index = 0;
-        let decode_table = self.alpha.decode_table();
+        let fast_decode_bytes = end.saturating_sub(trailing_bytes_to_skip);
 
-        {
-            let fast_decode_bytes = input.len().saturating_sub(trailing_bytes_to_skip);
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        let (mut input_index, mut output_index) = crate::simd::decode(
+            self.alpha.decode_table(),
+            output,
+            &input[..fast_decode_bytes],
+        );
+        #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+        let (mut input_index, mut output_index) = (0, 0);
 
-            if let Some(max_index) = fast_decode_bytes.checked_sub(INPUT_BLOCK_LEN) {
-                while input_index <= max_index {
-                    let output_block = &mut output
-                        [output_index..(output_index + OUTPUT_BLOCK_LEN + OUTPUT_BLOCK_OVERHEAD)];
-                    let input_block = &input[input_index..(input_index + INPUT_BLOCK_LEN)];
+        chunks -= input_index / INPUT_CHUNK_LEN;
 
-                    decode_chunk(
-                        decode_table,
-                        &mut output_block[0..],
-                        &input_block[0..],
-                        input_index,
-                    )?;
-                    decode_chunk(
-                        decode_table,
-                        &mut output_block[5..],
-                        &input_block[8..],
-                        input_index,
-                    )?;
-                    decode_chunk(
-                        decode_table,
-                        &mut output_block[10..],
-                        &input_block[16..],
-                        input_index,
-                    )?;
-                    decode_chunk(
-                        decode_table,
-                        &mut output_block[15..],
-                        &input_block[24..],
+        {
+            if let Some(max_index) = fast_decode_bytes.checked_sub(INPUT_BLOCK_LEN) {
+                while input_index <= max_index
+                    && output.len() - output_index >= OUTPUT_BLOCK_LEN + OUTPUT_BLOCK_OVERHEAD
+                {
+                    decode_block(
+                        self,
+                        &mut output[output_index
+                            ..(output_index + OUTPUT_BLOCK_LEN + OUTPUT_BLOCK_OVERHEAD)],
+                        &input[input_index..(input_index + INPUT_BLOCK_LEN)],
                         input_index,
-                    )?;
+                    )
+                    .map_err(|e| self.padding_error(e))?;
 
                     output_index += OUTPUT_BLOCK_LEN;
                     input_index += INPUT_BLOCK_LEN;
@@ -148,13 +311,17 @@ impl Encoding {
             }
 
             if let Some(max_index) = fast_decode_bytes.checked_sub(INPUT_CHUNK_LEN) {
-                while input_index < max_index {
+                while input_index < max_index
+                    && output.len() - output_index >= OUTPUT_CHUNK_LEN + OUTPUT_BLOCK_OVERHEAD
+                {
                     decode_chunk(
-                        decode_table,
-                        &mut output[output_index..(output_index + OUTPUT_CHUNK_LEN + 3)],
+                        self,
+                        &mut output[output_index
+                            ..(output_index + OUTPUT_CHUNK_LEN + OUTPUT_BLOCK_OVERHEAD)],
                         &input[input_index..(input_index + INPUT_CHUNK_LEN)],
                         input_index,
-                    )?;
+                    )
+                    .map_err(|e| self.padding_error(e))?;
 
                     output_index += OUTPUT_CHUNK_LEN;
                     input_index += INPUT_CHUNK_LEN;
@@ -165,35 +332,177 @@ impl Encoding {
 
         for _ in 1..chunks {
             decode_chunk_precise(
-                decode_table,
+                self,
                 &mut output[output_index..(output_index + OUTPUT_CHUNK_LEN)],
                 &input[input_index..(input_index + INPUT_CHUNK_LEN)],
                 input_index,
-            )?;
+            )
+            .map_err(|e| self.padding_error(e))?;
 
             output_index += OUTPUT_CHUNK_LEN;
             input_index += INPUT_CHUNK_LEN;
         }
 
-        debug_assert!(input.len() - input_index > 1 || input.is_empty());
-        debug_assert!(input.len() - input_index <= INPUT_CHUNK_LEN);
+        self.decode_tail(output, input, output_index, input_index)
+    }
+
+    /// Same as `decode_to_slice`, except that ignored bytes are skipped. Runs of
+    /// input without any ignored bytes still go through the fast paths.
+    fn decode_ignoring_to_slice(
+        &self,
+        output: &mut [u8],
+        input: &[u8],
+    ) -> Result<usize, DecodeError> {
+        let ignored = &self.ignore;
+        let padding = self.trailing_padding(input.iter().filter(|b| !ignored.contains(**b)));
+        let symbols = input.iter().filter(|b| !ignored.contains(**b)).count() - padding;
+
+        // invalid unpadded input lengths, padded ones are left to the tail ...
+        if padding == 0 && matches!(symbols % INPUT_CHUNK_LEN, 1 | 3 | 6) {
+            return Err(self.length_error(input));
+        }
+
+        let mut chunks = (symbols + INPUT_CHUNK_LEN - 1) / INPUT_CHUNK_LEN;
+        let mut output_index = 0;
+        let mut input_index = 0;
+
+        // an ignored byte only shows up as invalid in the fast paths,
+        // in which case the chunk is gathered one symbol at a time
+        let is_ignored = |e| match e {
+            DecodeError::InvalidByte(i, _) => ignored.contains(input[i]),
+            _ => false,
+        };
+
+        while chunks > 1 {
+            // the block writes past its end, so it must be followed by another full chunk
+            if chunks > 5
+                && input.len() - input_index >= INPUT_BLOCK_LEN
+                && output.len() - output_index >= OUTPUT_BLOCK_LEN + OUTPUT_BLOCK_OVERHEAD
+            {
+                let result = decode_block(
+                    self,
+                    &mut output
+                        [output_index..(output_index + OUTPUT_BLOCK_LEN + OUTPUT_BLOCK_OVERHEAD)],
+                    &input[input_index..(input_index + INPUT_BLOCK_LEN)],
+                    input_index,
+                );
+                match result {
+                    Ok(()) => {
+                        output_index += OUTPUT_BLOCK_LEN;
+                        input_index += INPUT_BLOCK_LEN;
+                        chunks -= 4;
+                        continue;
+                    }
+                    Err(e) if !is_ignored(e) => return Err(self.padding_error(e)),
+                    Err(_) => {}
+                }
+            }
+
+            if input.len() - input_index >= INPUT_CHUNK_LEN {
+                let result = decode_chunk_precise(
+                    self,
+                    &mut output[output_index..(output_index + OUTPUT_CHUNK_LEN)],
+                    &input[input_index..(input_index + INPUT_CHUNK_LEN)],
+                    input_index,
+                );
+                match result {
+                    Ok(()) => {
+                        output_index += OUTPUT_CHUNK_LEN;
+                        input_index += INPUT_CHUNK_LEN;
+                        chunks -= 1;
+                        continue;
+                    }
+                    Err(e) if !is_ignored(e) => return Err(self.padding_error(e)),
+                    Err(_) => {}
+                }
+            }
+
+            let mut chunk = [0; INPUT_CHUNK_LEN];
+            let mut positions = [0; INPUT_CHUNK_LEN];
+            let mut len = 0;
+            while len < INPUT_CHUNK_LEN {
+                if !ignored.contains(input[input_index]) {
+                    chunk[len] = input[input_index];
+                    positions[len] = input_index;
+                    len += 1;
+                }
+                input_index += 1;
+            }
+
+            decode_chunk_precise(
+                self,
+                &mut output[output_index..(output_index + OUTPUT_CHUNK_LEN)],
+                &chunk,
+                0,
+            )
+            .map_err(|e| match e {
+                DecodeError::InvalidByte(i, b) => {
+                    self.padding_error(DecodeError::InvalidByte(positions[i], b))
+                }
+                e => e,
+            })?;
+
+            output_index += OUTPUT_CHUNK_LEN;
+            chunks -= 1;
+        }
+
+        self.decode_tail(output, input, output_index, input_index)
+    }
 
+    /// Decodes the final chunk of the input, starting at `input_index`,
+    /// which may be partial or padded.
+    fn decode_tail(
+        &self,
+        output: &mut [u8],
+        input: &[u8],
+        mut output_index: usize,
+        input_index: usize,
+    ) -> Result<usize, DecodeError> {
+        let decode_table = self.alpha.decode_table();
         let mut buffer: u64 = 0;
         let mut quintets = 0;
+        let mut last_index = input_index;
+        let mut pad_index = None;
+        let mut pad_bytes = 0;
 
         for (i, b) in input[input_index..].iter().enumerate() {
-            if let Some(pad) = self.pad {
-                if *b == pad {
-                    break;
+            if self.ignore.contains(*b) {
+                continue;
+            }
+            if Some(*b) == self.pad {
+                if self.padding == PaddingMode::Forbidden {
+                    return Err(DecodeError::UnexpectedPadding(input_index + i));
                 }
+                pad_index = pad_index.or(Some(input_index + i));
+                pad_bytes += 1;
+                continue;
             }
 
             let quintet = decode_table[*b as usize];
-            if quintet == INVALID_BYTE {
+            if quintet == INVALID_BYTE || pad_index.is_some() {
                 return Err(DecodeError::InvalidByte(input_index + i, *b));
             }
             buffer |= (quintet as u64) << (64 - (quintets + 1) * 5);
             quintets += 1;
+            last_index = input_index + i;
+        }
+
+        if let Some(i) = pad_index {
+            // padding must follow a valid amount of symbols and fill up the rest of the chunk,
+            // though it may stop short of that unless it's required
+            let len = quintets + pad_bytes;
+            let fits = match self.padding {
+                PaddingMode::Required => len == INPUT_CHUNK_LEN,
+                _ => len <= INPUT_CHUNK_LEN,
+            };
+            if !fits || matches!(quintets, 0 | 1 | 3 | 6) {
+                return Err(DecodeError::InvalidPadding(i));
+            }
+        } else if self.pad.is_some()
+            && self.padding == PaddingMode::Required
+            && quintets % INPUT_CHUNK_LEN != 0
+        {
+            return Err(DecodeError::MissingPadding);
         }
 
         let bits_to_append = match quintets {
@@ -206,6 +515,15 @@ impl Encoding {
             n => unreachable!("Invalid leftover quintet count: {}", n),
         };
 
+        // any bits that don't make up a whole byte are discarded, and
+        // must be zero for the encoding to be the only possible one
+        if self.canonical && buffer << bits_to_append != 0 {
+            return Err(DecodeError::InvalidLastSymbol(
+                last_index,
+                input[last_index],
+            ));
+        }
+
         let mut bits_appended = 0;
         while bits_appended < bits_to_append {
             let bits = (buffer >> (56 - bits_appended)) as u8;
@@ -220,60 +538,76 @@ impl Encoding {
     }
 }
 
+// Every symbol is checked as soon as it's looked up. ORing the lookups of a chunk or a block
+// together and checking for INVALID_BYTE once, rescanning only on failure, was no faster on
+// the decode_to_slice benchmarks: the branches predict perfectly on valid input, and an OR
+// per symbol costs as much as a fused compare and branch.
 #[inline(always)]
 fn decode_chunk(
-    decode_table: &'static [u8; 256],
+    encoding: &Encoding,
     output_chunk: &mut [u8],
     input_chunk: &[u8],
     starting_index: usize,
 ) -> Result<(), DecodeError> {
+    #[cfg(feature = "pair-decode")]
+    if let Some(pairs) = encoding.pair_decode {
+        return decode_chunk_pairs(
+            encoding,
+            pairs.table(),
+            output_chunk,
+            input_chunk,
+            starting_index,
+        );
+    }
+
+    let decode_table = encoding.alpha.decode_table();
     let mut data: u64 = 0;
 
     let quintet = decode_table[input_chunk[0] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index, quintet));
+        return Err(DecodeError::InvalidByte(starting_index, input_chunk[0]));
     }
     data |= (quintet as u64) << 59;
 
     let quintet = decode_table[input_chunk[1] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 1, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 1, input_chunk[1]));
     }
     data |= (quintet as u64) << 54;
 
     let quintet = decode_table[input_chunk[2] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 2, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 2, input_chunk[2]));
     }
     data |= (quintet as u64) << 49;
 
     let quintet = decode_table[input_chunk[3] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 3, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 3, input_chunk[3]));
     }
     data |= (quintet as u64) << 44;
 
     let quintet = decode_table[input_chunk[4] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 4, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 4, input_chunk[4]));
     }
     data |= (quintet as u64) << 39;
 
     let quintet = decode_table[input_chunk[5] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 5, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 5, input_chunk[5]));
     }
     data |= (quintet as u64) << 34;
 
     let quintet = decode_table[input_chunk[6] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 6, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 6, input_chunk[6]));
     }
     data |= (quintet as u64) << 29;
 
     let quintet = decode_table[input_chunk[7] as usize];
     if quintet == INVALID_BYTE {
-        return Err(DecodeError::InvalidByte(starting_index + 7, quintet));
+        return Err(DecodeError::InvalidByte(starting_index + 7, input_chunk[7]));
     }
     data |= (quintet as u64) << 24;
 
@@ -282,16 +616,111 @@ fn decode_chunk(
     Ok(())
 }
 
+/// Like [`decode_chunk`], but looks up the symbols two at a time.
+#[cfg(feature = "pair-decode")]
+#[inline(always)]
+fn decode_chunk_pairs(
+    encoding: &Encoding,
+    pair_table: &[u16; 65536],
+    output_chunk: &mut [u8],
+    input_chunk: &[u8],
+    starting_index: usize,
+) -> Result<(), DecodeError> {
+    let pair = |i: usize| (input_chunk[i] as usize) << 8 | input_chunk[i + 1] as usize;
+    let mut data: u64 = 0;
+
+    let decade = pair_table[pair(0)];
+    if decade == INVALID_PAIR {
+        return Err(invalid_pair(encoding, input_chunk, 0, starting_index));
+    }
+    data |= (decade as u64) << 54;
+
+    let decade = pair_table[pair(2)];
+    if decade == INVALID_PAIR {
+        return Err(invalid_pair(encoding, input_chunk, 2, starting_index));
+    }
+    data |= (decade as u64) << 44;
+
+    let decade = pair_table[pair(4)];
+    if decade == INVALID_PAIR {
+        return Err(invalid_pair(encoding, input_chunk, 4, starting_index));
+    }
+    data |= (decade as u64) << 34;
+
+    let decade = pair_table[pair(6)];
+    if decade == INVALID_PAIR {
+        return Err(invalid_pair(encoding, input_chunk, 6, starting_index));
+    }
+    data |= (decade as u64) << 24;
+
+    output_chunk[..8].copy_from_slice(&data.to_be_bytes());
+
+    Ok(())
+}
+
+/// Reports whichever of the two symbols starting at `index` made the pair invalid.
+#[cfg(feature = "pair-decode")]
+#[cold]
+#[inline(never)]
+fn invalid_pair(
+    encoding: &Encoding,
+    input_chunk: &[u8],
+    index: usize,
+    starting_index: usize,
+) -> DecodeError {
+    let index = match encoding.alpha.decode_table()[input_chunk[index] as usize] {
+        INVALID_BYTE => index,
+        _ => index + 1,
+    };
+
+    DecodeError::InvalidByte(starting_index + index, input_chunk[index])
+}
+
+#[inline(always)]
+fn decode_block(
+    encoding: &Encoding,
+    output_block: &mut [u8],
+    input_block: &[u8],
+    starting_index: usize,
+) -> Result<(), DecodeError> {
+    decode_chunk(
+        encoding,
+        &mut output_block[0..],
+        &input_block[0..],
+        starting_index,
+    )?;
+    decode_chunk(
+        encoding,
+        &mut output_block[5..],
+        &input_block[8..],
+        starting_index + 8,
+    )?;
+    decode_chunk(
+        encoding,
+        &mut output_block[10..],
+        &input_block[16..],
+        starting_index + 16,
+    )?;
+    decode_chunk(
+        encoding,
+        &mut output_block[15..],
+        &input_block[24..],
+        starting_index + 24,
+    )?;
+
+    Ok(())
+}
+
 #[inline(always)]
 fn decode_chunk_precise(
-    decode_table: &'static [u8; 256],
+    encoding: &Encoding,
     output_chunk: &mut [u8],
     input_chunk: &[u8],
     starting_index: usize,
 ) -> Result<(), DecodeError> {
     let mut buffer = [0; 8];
 
-    decode_chunk(decode_table, &mut buffer, input_chunk, starting_index)?;
+    decode_chunk(encoding, &mut buffer, input_chunk, starting_index)?;
     output_chunk[0..5].copy_from_slice(&buffer[0..5]);
 
     Ok(())
@@ -0,0 +1,87 @@
+//! Formatting encoded data without allocating.
+use core::fmt::{self, Write};
+use core::str;
+
+use crate::Encoding;
+
+const INPUT_CHUNK_LEN: usize = 5;
+const OUTPUT_BUF_LEN: usize = 8 * 64;
+
+/// A wrapper around some data that encodes it when formatted through
+/// [`Display`](fmt::Display) or [`Debug`](fmt::Debug), which is returned by
+/// [`Encoding::display`].
+///
+/// The data is encoded a piece at a time into a buffer on the stack and written straight to the
+/// formatter, so this works without the `alloc` feature as well. The width, fill and alignment
+/// of the formatter apply to the encoded data as a whole, the same as for a string.
+/// ```
+/// let display = base32::STANDARD.display(b"foobar");
+///
+/// assert_eq!(format!("{}", display), "MZXW6YTBOI======");
+/// assert_eq!(format!("{:?}", display), "\"MZXW6YTBOI======\"");
+/// assert_eq!(format!("{:>10}", base32::STANDARD.display(b"f")), "  MY======");
+/// ```
+#[derive(Copy, Clone)]
+pub struct Base32Display<'a> {
+    encoding: &'a Encoding,
+    data: &'a [u8],
+}
+
+impl Encoding {
+    /// Returns a wrapper that encodes the data according to
+    /// the configuration whenever it's formatted.
+    #[inline]
+    pub fn display<'a>(&'a self, data: &'a [u8]) -> Base32Display<'a> {
+        Base32Display {
+            encoding: self,
+            data,
+        }
+    }
+}
+
+impl fmt::Display for Base32Display<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the output is all ASCII, so its length in bytes is also its width
+        let fill = match (fmt.width(), self.encoding.encoded_size(self.data.len())) {
+            (Some(width), Some(len)) if len < width => width - len,
+            _ => 0,
+        };
+        let (before, after) = match fmt.align() {
+            Some(fmt::Alignment::Right) => (fill, 0),
+            Some(fmt::Alignment::Center) => (fill / 2, fill - fill / 2),
+            _ => (0, fill),
+        };
+        write_fill(fmt, before)?;
+
+        let mut buf = [0; OUTPUT_BUF_LEN];
+        let mut index = 0;
+
+        // a multiple of the input chunk length, so that only the final piece is padded
+        let piece_len = OUTPUT_BUF_LEN / self.encoding.max_chunk_len() * INPUT_CHUNK_LEN;
+
+        for chunk in self.data.chunks(piece_len) {
+            let symbols = self.encoding.encode_symbols_to_slice(&mut buf, chunk);
+            let written = self.encoding.insert_separators(&mut buf, index, symbols);
+            index += symbols;
+
+            fmt.write_str(str::from_utf8(&buf[..written]).map_err(|_| fmt::Error)?)?;
+        }
+
+        write_fill(fmt, after)
+    }
+}
+
+impl fmt::Debug for Base32Display<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "\"{}\"", self)
+    }
+}
+
+fn write_fill(fmt: &mut fmt::Formatter<'_>, count: usize) -> fmt::Result {
+    let fill = fmt.fill();
+    for _ in 0..count {
+        fmt.write_char(fill)?;
+    }
+
+    Ok(())
+}
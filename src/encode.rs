@@ -1,5 +1,10 @@
 #[cfg(any(feature = "alloc", feature = "std", test))]
 use alloc::{string::String, vec};
+#[cfg(any(feature = "alloc", feature = "std", test))]
+use core::fmt::Write;
+use core::{cmp, fmt, slice};
+#[cfg(any(feature = "std", test))]
+use std::error;
 
 use crate::Encoding;
 
@@ -13,6 +18,45 @@ pub fn encode(data: impl AsRef<[u8]>) -> String {
     crate::STANDARD.encode(data)
 }
 
+/// Errors that can occur while encoding
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// Returned if the output slice is too small to hold the encoded data.
+    OutputBufferTooSmall,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::OutputBufferTooSmall => write!(fmt, "output buffer too small"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for EncodeError {}
+
+/// The line ending inserted between lines of encoded
+/// output, see [`Encoding::with_line_wrap`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// A single line feed, `\n`.
+    Lf,
+    /// A carriage return followed by a line feed, `\r\n`.
+    CrLf,
+}
+
+impl LineEnding {
+    #[inline]
+    pub(crate) const fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
 // Each fast loop reads four 40-bit (5 bytes) blocks of input as u64. So we
 // need 24 bits (3 bytes) of trailing information available each iteration.
 const INPUT_BLOCK_OVERHEAD: usize = 3;
@@ -42,10 +86,39 @@ impl Encoding {
         String::from_utf8(buf).expect("Implementation error")
     }
 
-    /// Calculates the required output buffer size when encoding the
-    /// given amount of bytes, returns `None` in case of overflow.
+    /// Encodes arbitrary input bytes according to the chosen configuration,
+    /// appending it to the string. The string is grown at most once.
+    /// ```
+    /// let mut buf = String::from("MZXW6===");
+    /// base32::STANDARD.encode_to_string("bar", &mut buf);
+    ///
+    /// assert_eq!(buf, "MZXW6===MJQXE===");
+    /// ```
+    #[cfg(any(feature = "alloc", feature = "std", test))]
+    pub fn encode_to_string(&self, data: impl AsRef<[u8]>, output: &mut String) {
+        let data = data.as_ref();
+        let encoded_size = self
+            .encoded_size(data.len())
+            .expect("Overflowed while calculating encoded size of input");
+
+        output.reserve(encoded_size);
+        write!(output, "{}", self.display(data)).expect("Implementation error");
+    }
+
+    /// Calculates the required output buffer size when encoding the given amount
+    /// of bytes, including any line endings. Returns `None` in case of overflow.
     #[inline]
-    pub fn encoded_size(self, input_bytes: usize) -> Option<usize> {
+    pub fn encoded_size(&self, input_bytes: usize) -> Option<usize> {
+        let symbols = self.encoded_symbols(input_bytes)?;
+
+        self.separators_len(0, symbols)
+            .and_then(|n| n.checked_add(symbols))
+    }
+
+    /// Calculates how many symbols the given amount of bytes encode to, including
+    /// padding but not line endings. Returns `None` in case of overflow.
+    #[inline]
+    fn encoded_symbols(&self, input_bytes: usize) -> Option<usize> {
         if self.pad.is_some() {
             input_bytes
                 .checked_add(4)
@@ -59,96 +132,154 @@ impl Encoding {
         }
     }
 
+    /// Same as [`encode_to_slice`](Encoding::encode_to_slice), except that it returns
+    /// [`EncodeError::OutputBufferTooSmall`] instead of panicking if the output slice
+    /// can't hold the encoded data.
+    /// ```
+    /// let mut buf = [0; 8];
+    /// assert_eq!(base32::STANDARD.try_encode_to_slice(&mut buf, b"foo"), Ok(8));
+    /// assert_eq!(&buf, b"MZXW6===");
+    ///
+    /// assert!(base32::STANDARD.try_encode_to_slice(&mut buf, b"foobar").is_err());
+    /// ```
+    pub fn try_encode_to_slice(
+        &self,
+        output: &mut [u8],
+        input: &[u8],
+    ) -> Result<usize, EncodeError> {
+        match self.encoded_size(input.len()) {
+            Some(len) if len <= output.len() => Ok(self.encode_to_slice(output, input)),
+            _ => Err(EncodeError::OutputBufferTooSmall),
+        }
+    }
+
     /// Takes a slice of arbitrary bytes and encodes it according to the
     /// configuration, writing the resulting data into the output slice.
     pub fn encode_to_slice(&self, output: &mut [u8], input: &[u8]) -> usize {
-        let mut output_index = 0;
-        let mut input_index = 0;
-        let encode_table = self.alpha.encode_table();
+        let symbols = self.encode_symbols_to_slice(output, input);
 
-        let last_fast_index = input
-            .len()
-            .saturating_sub(INPUT_BLOCK_LEN + INPUT_BLOCK_OVERHEAD);
-
-        if last_fast_index > 0 {
-            while input_index <= last_fast_index {
-                let output_block = &mut output[output_index..output_index + OUTPUT_BLOCK_LEN];
-                let input_block =
-                    &input[input_index..input_index + INPUT_BLOCK_LEN + INPUT_BLOCK_OVERHEAD];
-
-                let batch = [
-                    read_u64(&input_block[0..]),
-                    read_u64(&input_block[5..]),
-                    read_u64(&input_block[10..]),
-                    read_u64(&input_block[15..]),
-                ];
-
-                const LOW_FIVE_BITS: u64 = 0b11111;
-
-                output_block[0] = encode_table[((batch[0] >> 59) & LOW_FIVE_BITS) as usize];
-                output_block[1] = encode_table[((batch[0] >> 54) & LOW_FIVE_BITS) as usize];
-                output_block[2] = encode_table[((batch[0] >> 49) & LOW_FIVE_BITS) as usize];
-                output_block[3] = encode_table[((batch[0] >> 44) & LOW_FIVE_BITS) as usize];
-                output_block[4] = encode_table[((batch[0] >> 39) & LOW_FIVE_BITS) as usize];
-                output_block[5] = encode_table[((batch[0] >> 34) & LOW_FIVE_BITS) as usize];
-                output_block[6] = encode_table[((batch[0] >> 29) & LOW_FIVE_BITS) as usize];
-                output_block[7] = encode_table[((batch[0] >> 24) & LOW_FIVE_BITS) as usize];
-
-                output_block[8] = encode_table[((batch[1] >> 59) & LOW_FIVE_BITS) as usize];
-                output_block[9] = encode_table[((batch[1] >> 54) & LOW_FIVE_BITS) as usize];
-                output_block[10] = encode_table[((batch[1] >> 49) & LOW_FIVE_BITS) as usize];
-                output_block[11] = encode_table[((batch[1] >> 44) & LOW_FIVE_BITS) as usize];
-                output_block[12] = encode_table[((batch[1] >> 39) & LOW_FIVE_BITS) as usize];
-                output_block[13] = encode_table[((batch[1] >> 34) & LOW_FIVE_BITS) as usize];
-                output_block[14] = encode_table[((batch[1] >> 29) & LOW_FIVE_BITS) as usize];
-                output_block[15] = encode_table[((batch[1] >> 24) & LOW_FIVE_BITS) as usize];
-
-                output_block[16] = encode_table[((batch[2] >> 59) & LOW_FIVE_BITS) as usize];
-                output_block[17] = encode_table[((batch[2] >> 54) & LOW_FIVE_BITS) as usize];
-                output_block[18] = encode_table[((batch[2] >> 49) & LOW_FIVE_BITS) as usize];
-                output_block[19] = encode_table[((batch[2] >> 44) & LOW_FIVE_BITS) as usize];
-                output_block[20] = encode_table[((batch[2] >> 39) & LOW_FIVE_BITS) as usize];
-                output_block[21] = encode_table[((batch[2] >> 34) & LOW_FIVE_BITS) as usize];
-                output_block[22] = encode_table[((batch[2] >> 29) & LOW_FIVE_BITS) as usize];
-                output_block[23] = encode_table[((batch[2] >> 24) & LOW_FIVE_BITS) as usize];
-
-                output_block[24] = encode_table[((batch[3] >> 59) & LOW_FIVE_BITS) as usize];
-                output_block[25] = encode_table[((batch[3] >> 54) & LOW_FIVE_BITS) as usize];
-                output_block[26] = encode_table[((batch[3] >> 49) & LOW_FIVE_BITS) as usize];
-                output_block[27] = encode_table[((batch[3] >> 44) & LOW_FIVE_BITS) as usize];
-                output_block[28] = encode_table[((batch[3] >> 39) & LOW_FIVE_BITS) as usize];
-                output_block[29] = encode_table[((batch[3] >> 34) & LOW_FIVE_BITS) as usize];
-                output_block[30] = encode_table[((batch[3] >> 29) & LOW_FIVE_BITS) as usize];
-                output_block[31] = encode_table[((batch[3] >> 24) & LOW_FIVE_BITS) as usize];
-
-                output_index += OUTPUT_BLOCK_LEN;
-                input_index += INPUT_BLOCK_LEN;
-            }
+        self.insert_separators(output, 0, symbols)
+    }
+
+    /// Returns the separator that goes in front of the symbol at the given index of the
+    /// encoded output as a whole, which is empty for most symbols. Line endings take
+    /// precedence over group separators.
+    #[inline]
+    pub(crate) fn separator(&self, index: usize) -> &[u8] {
+        match (self.wrap, &self.group) {
+            _ if index == 0 => &[],
+            (Some((width, ending)), _) if index % width == 0 => ending.as_bytes(),
+            (_, Some((size, separator))) if index % *size == 0 => slice::from_ref(separator),
+            _ => &[],
         }
+    }
 
-        const LOW_FIVE_BITS: u8 = 0b11111;
+    /// Returns the index of the last symbol before `end` that has a separator in
+    /// front of it, or 0 if there are none.
+    #[inline]
+    fn last_separator_before(&self, end: usize) -> usize {
+        let last_multiple = |width: usize| end.saturating_sub(1) / width * width;
+
+        cmp::max(
+            self.wrap.map_or(0, |(width, _)| last_multiple(width)),
+            self.group.map_or(0, |(size, _)| last_multiple(size)),
+        )
+    }
+
+    /// Calculates how many bytes of separators go in front of the `len` symbols starting at
+    /// `index` of the encoded output as a whole. Returns `None` in case of overflow.
+    #[inline]
+    fn separators_len(&self, index: usize, len: usize) -> Option<usize> {
+        let lines = match self.wrap {
+            Some((width, ending)) => {
+                count_multiples(width, index, len).checked_mul(ending.as_bytes().len())?
+            }
+            None => 0,
+        };
+        let groups = match (self.wrap, self.group) {
+            (Some((width, _)), Some((size, _))) => {
+                // group separators are replaced by line endings wherever both would go
+                let both = (size / gcd(size, width))
+                    .checked_mul(width)
+                    .map_or(0, |lcm| count_multiples(lcm, index, len));
+                count_multiples(size, index, len) - both
+            }
+            (None, Some((size, _))) => count_multiples(size, index, len),
+            (_, None) => 0,
+        };
 
-        while input.len() - input_index >= 5 {
-            let output_chunk = &mut output[output_index..output_index + OUTPUT_CHUNK_LEN];
-            let input_chunk = &input[input_index..input_index + INPUT_CHUNK_LEN];
-
-            output_chunk[0] = encode_table[(input_chunk[0] >> 3) as usize];
-            output_chunk[1] = encode_table
-                [((input_chunk[0] << 2 | input_chunk[1] >> 6) & LOW_FIVE_BITS) as usize];
-            output_chunk[2] = encode_table[((input_chunk[1] >> 1) & LOW_FIVE_BITS) as usize];
-            output_chunk[3] = encode_table
-                [((input_chunk[1] << 4 | input_chunk[2] >> 4) & LOW_FIVE_BITS) as usize];
-            output_chunk[4] = encode_table
-                [((input_chunk[2] << 1 | input_chunk[3] >> 7) & LOW_FIVE_BITS) as usize];
-            output_chunk[5] = encode_table[((input_chunk[3] >> 2) & LOW_FIVE_BITS) as usize];
-            output_chunk[6] = encode_table
-                [((input_chunk[3] << 3 | input_chunk[4] >> 5) & LOW_FIVE_BITS) as usize];
-            output_chunk[7] = encode_table[(input_chunk[4] & LOW_FIVE_BITS) as usize];
-
-            output_index += OUTPUT_CHUNK_LEN;
-            input_index += INPUT_CHUNK_LEN;
+        lines.checked_add(groups)
+    }
+
+    /// Returns the most bytes that a single chunk of symbols
+    /// can take up in the output, including separators.
+    #[inline]
+    pub(crate) fn max_chunk_len(&self) -> usize {
+        let lines = self.wrap.map_or(0, |(width, ending)| {
+            (OUTPUT_CHUNK_LEN / width + 1) * ending.as_bytes().len()
+        });
+        let groups = self
+            .group
+            .map_or(0, |(size, _)| OUTPUT_CHUNK_LEN / size + 1);
+
+        OUTPUT_CHUNK_LEN + lines + groups
+    }
+
+    /// Spreads out the `len` symbols at the start of the buffer to make room for separators
+    /// in between, returning the length including separators. `index` is the index of the
+    /// first symbol in the encoded output as a whole.
+    pub(crate) fn insert_separators(&self, buf: &mut [u8], index: usize, len: usize) -> usize {
+        if self.wrap.is_none() && self.group.is_none() {
+            return len;
         }
 
+        let total = len
+            + self
+                .separators_len(index, len)
+                .expect("Overflowed while calculating length of separators");
+        let mut end = index + len;
+        let mut buf_end = total;
+
+        // work backwards, so that symbols are only ever moved to where they've already been read
+        while end > index {
+            let start = cmp::max(self.last_separator_before(end), index);
+            let symbols = end - start;
+            buf.copy_within(start - index..end - index, buf_end - symbols);
+            buf_end -= symbols;
+
+            let separator = self.separator(start);
+            buf[buf_end - separator.len()..buf_end].copy_from_slice(separator);
+            buf_end -= separator.len();
+
+            end = start;
+        }
+
+        debug_assert_eq!(buf_end, 0);
+
+        total
+    }
+
+    /// Encodes the input according to the configuration like `encode_to_slice`,
+    /// but without inserting any separators.
+    pub(crate) fn encode_symbols_to_slice(&self, output: &mut [u8], input: &[u8]) -> usize {
+        let encode_table = self.alpha.encode_table();
+
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        let (input_index, output_index) = crate::simd::encode(encode_table, output, input);
+        #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+        let (input_index, output_index) = (0, 0);
+
+        let (input_index, mut output_index) = match self.pair_encode {
+            Some(table) => encode_chunks(output, input, input_index, output_index, |out, bits| {
+                encode_chunk_pairs(table.table(), out, bits)
+            }),
+            None => encode_chunks(output, input, input_index, output_index, |out, bits| {
+                encode_chunk(encode_table, out, bits)
+            }),
+        };
+
+        const LOW_FIVE_BITS: u8 = 0b11111;
+
         match input.len() - input_index {
             4 => {
                 let output_chunk = &mut output[output_index..output_index + 7];
@@ -244,6 +375,110 @@ impl Encoding {
     }
 }
 
+/// Counts the symbols among the `len` symbols starting at `index` that are preceded
+/// by a multiple of `width` symbols, not counting the very first symbol of the output.
+#[inline]
+fn count_multiples(width: usize, index: usize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+
+    let before = index.checked_sub(1).map_or(0, |n| n / width + 1);
+    let multiples = (index + len - 1) / width + 1 - before;
+
+    if index == 0 {
+        multiples - 1
+    } else {
+        multiples
+    }
+}
+
+#[inline]
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Encodes whole chunks of the input, in blocks of four where there's enough input left
+/// over to read past the end of the block, and returns how far it got.
+#[inline(always)]
+fn encode_chunks(
+    output: &mut [u8],
+    input: &[u8],
+    mut input_index: usize,
+    mut output_index: usize,
+    encode_chunk: impl Fn(&mut [u8], u64),
+) -> (usize, usize) {
+    while input.len() - input_index >= INPUT_BLOCK_LEN + INPUT_BLOCK_OVERHEAD {
+        let output_block = &mut output[output_index..output_index + OUTPUT_BLOCK_LEN];
+        let input_block = &input[input_index..input_index + INPUT_BLOCK_LEN + INPUT_BLOCK_OVERHEAD];
+
+        let batch = [
+            read_u64(&input_block[0..]),
+            read_u64(&input_block[5..]),
+            read_u64(&input_block[10..]),
+            read_u64(&input_block[15..]),
+        ];
+
+        encode_chunk(&mut output_block[0..], batch[0]);
+        encode_chunk(&mut output_block[8..], batch[1]);
+        encode_chunk(&mut output_block[16..], batch[2]);
+        encode_chunk(&mut output_block[24..], batch[3]);
+
+        output_index += OUTPUT_BLOCK_LEN;
+        input_index += INPUT_BLOCK_LEN;
+    }
+
+    // short inputs never make it to the block loop, so whole chunks are
+    // encoded the same way instead of a symbol at a time
+    while input.len() - input_index >= INPUT_CHUNK_LEN {
+        let input_chunk = &input[input_index..input_index + INPUT_CHUNK_LEN];
+        let bits = u64::from_be_bytes([
+            input_chunk[0],
+            input_chunk[1],
+            input_chunk[2],
+            input_chunk[3],
+            input_chunk[4],
+            0,
+            0,
+            0,
+        ]);
+
+        encode_chunk(&mut output[output_index..], bits);
+
+        output_index += OUTPUT_CHUNK_LEN;
+        input_index += INPUT_CHUNK_LEN;
+    }
+
+    (input_index, output_index)
+}
+
+/// Encodes the upper 40 bits of `bits` into the first eight bytes of the output.
+#[inline(always)]
+fn encode_chunk(encode_table: &[u8; 32], output_chunk: &mut [u8], bits: u64) {
+    const LOW_FIVE_BITS: u64 = 0b11111;
+
+    for (i, symbol) in output_chunk[..OUTPUT_CHUNK_LEN].iter_mut().enumerate() {
+        *symbol = encode_table[((bits >> (59 - i * 5)) & LOW_FIVE_BITS) as usize];
+    }
+}
+
+/// Encodes the upper 40 bits of `bits` into the first eight bytes of the output, looking
+/// up two symbols at a time.
+#[inline(always)]
+fn encode_chunk_pairs(pair_table: &[[u8; 2]; 1024], output_chunk: &mut [u8], bits: u64) {
+    const LOW_TEN_BITS: u64 = 0b11_1111_1111;
+
+    output_chunk[0..2].copy_from_slice(&pair_table[((bits >> 54) & LOW_TEN_BITS) as usize]);
+    output_chunk[2..4].copy_from_slice(&pair_table[((bits >> 44) & LOW_TEN_BITS) as usize]);
+    output_chunk[4..6].copy_from_slice(&pair_table[((bits >> 34) & LOW_TEN_BITS) as usize]);
+    output_chunk[6..8].copy_from_slice(&pair_table[((bits >> 24) & LOW_TEN_BITS) as usize]);
+}
+
 #[inline(always)]
 fn read_u64(bytes: &[u8]) -> u64 {
     use core::convert::TryInto;
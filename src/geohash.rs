@@ -0,0 +1,304 @@
+//! Encoding and decoding geohashes, which pack a latitude and longitude into symbols of
+//! [`Alphabet::GEOHASH`].
+//!
+//! The bits of a geohash alternate between longitude and latitude, starting with longitude,
+//! and each of them halves the range that the coordinate falls in. Every symbol added to a
+//! geohash therefore makes the cell it describes smaller.
+//! ```
+//! let hash = base32::geohash::encode(57.64911, 10.40744, 11).unwrap();
+//! assert_eq!(hash, "u4pruydqqvj");
+//!
+//! let (lat, lon, lat_err, lon_err) = base32::geohash::decode(&hash).unwrap();
+//! assert!((lat - 57.64911).abs() <= lat_err);
+//! assert!((lon - 10.40744).abs() <= lon_err);
+//! ```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+use alloc::{string::String, vec};
+use core::fmt;
+#[cfg(any(feature = "std", test))]
+use std::error;
+
+use crate::{Alphabet, DecodeError};
+
+/// The longest geohash that is encoded or decoded. Any longer and the cells shrink to only a
+/// few `f64` steps across, so that moving to a [`neighbor`] can round back into the same cell.
+pub const MAX_PRECISION: usize = 20;
+
+/// Errors that can occur while encoding coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum CoordinateError {
+    /// Returned if the latitude isn't between -90 and 90 degrees.
+    InvalidLatitude(f64),
+    /// Returned if the longitude isn't between -180 and 180 degrees.
+    InvalidLongitude(f64),
+    /// Returned if the geohash would be empty, as an empty geohash can't be decoded, or
+    /// longer than [`MAX_PRECISION`] symbols.
+    InvalidPrecision(usize),
+}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordinateError::InvalidLatitude(lat) => write!(fmt, "invalid latitude {}", lat),
+            CoordinateError::InvalidLongitude(lon) => write!(fmt, "invalid longitude {}", lon),
+            CoordinateError::InvalidPrecision(precision) => {
+                write!(fmt, "invalid precision {}", precision)
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for CoordinateError {}
+
+/// The cell described by a geohash, in degrees.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    /// The southern edge of the cell.
+    pub min_lat: f64,
+    /// The northern edge of the cell.
+    pub max_lat: f64,
+    /// The western edge of the cell.
+    pub min_lon: f64,
+    /// The eastern edge of the cell.
+    pub max_lon: f64,
+}
+
+impl BoundingBox {
+    const WORLD: BoundingBox = BoundingBox {
+        min_lat: -90.0,
+        max_lat: 90.0,
+        min_lon: -180.0,
+        max_lon: 180.0,
+    };
+
+    /// Returns the latitude and longitude of the center of the cell.
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.min_lat + self.max_lat) / 2.0,
+            (self.min_lon + self.max_lon) / 2.0,
+        )
+    }
+}
+
+/// The directions of the cells surrounding a geohash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// The cell to the north.
+    North,
+    /// The cell to the north-east.
+    NorthEast,
+    /// The cell to the east.
+    East,
+    /// The cell to the south-east.
+    SouthEast,
+    /// The cell to the south.
+    South,
+    /// The cell to the south-west.
+    SouthWest,
+    /// The cell to the west.
+    West,
+    /// The cell to the north-west.
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north, in the order returned by [`neighbors`].
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// Returns how many cells to move north and east to go in this direction.
+    #[cfg(any(feature = "alloc", feature = "std", test))]
+    fn offset(self) -> (f64, f64) {
+        match self {
+            Direction::North => (1.0, 0.0),
+            Direction::NorthEast => (1.0, 1.0),
+            Direction::East => (0.0, 1.0),
+            Direction::SouthEast => (-1.0, 1.0),
+            Direction::South => (-1.0, 0.0),
+            Direction::SouthWest => (-1.0, -1.0),
+            Direction::West => (0.0, -1.0),
+            Direction::NorthWest => (1.0, -1.0),
+        }
+    }
+}
+
+/// Encodes the coordinates into a geohash of `precision` symbols.
+/// ```
+/// use base32::geohash::{self, CoordinateError};
+///
+/// assert_eq!(geohash::encode(42.6, -5.6, 5).unwrap(), "ezs42");
+/// assert_eq!(geohash::encode(42.6, -5.6, 0), Err(CoordinateError::InvalidPrecision(0)));
+/// assert_eq!(geohash::encode(42.6, -5.6, 21), Err(CoordinateError::InvalidPrecision(21)));
+/// ```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode(lat: f64, lon: f64, precision: usize) -> Result<String, CoordinateError> {
+    check_precision(precision)?;
+
+    let mut buf = vec![0; precision];
+    encode_to_slice(&mut buf, lat, lon)?;
+
+    Ok(String::from_utf8(buf).expect("Implementation error"))
+}
+
+/// Encodes the coordinates into a geohash filling up the whole output slice, which must not
+/// be empty or longer than [`MAX_PRECISION`].
+pub fn encode_to_slice(output: &mut [u8], lat: f64, lon: f64) -> Result<(), CoordinateError> {
+    check_precision(output.len())?;
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err(CoordinateError::InvalidLatitude(lat));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Err(CoordinateError::InvalidLongitude(lon));
+    }
+
+    let mut bbox = BoundingBox::WORLD;
+    let mut is_lon = true;
+
+    for symbol in output {
+        let mut value = 0;
+        for _ in 0..5 {
+            let (min, max, coordinate) = if is_lon {
+                (&mut bbox.min_lon, &mut bbox.max_lon, lon)
+            } else {
+                (&mut bbox.min_lat, &mut bbox.max_lat, lat)
+            };
+            let upper = coordinate >= (*min + *max) / 2.0;
+
+            halve(min, max, upper);
+            value = value << 1 | upper as usize;
+            is_lon = !is_lon;
+        }
+
+        *symbol = Alphabet::GEOHASH.symbols()[value];
+    }
+
+    Ok(())
+}
+
+/// Decodes a geohash into the latitude and longitude at the center of its cell, followed by
+/// how far the latitude and longitude of any point within the cell can be from it.
+/// ```
+/// let (lat, lon, lat_err, lon_err) = base32::geohash::decode("ezs42").unwrap();
+///
+/// assert_eq!((lat, lon), (42.60498046875, -5.60302734375));
+/// assert_eq!((lat_err, lon_err), (0.02197265625, 0.02197265625));
+/// ```
+pub fn decode(hash: impl AsRef<[u8]>) -> Result<(f64, f64, f64, f64), DecodeError> {
+    let bbox = bounding_box(hash)?;
+    let (lat, lon) = bbox.center();
+
+    Ok((
+        lat,
+        lon,
+        (bbox.max_lat - bbox.min_lat) / 2.0,
+        (bbox.max_lon - bbox.min_lon) / 2.0,
+    ))
+}
+
+/// Decodes a geohash into the cell that it describes.
+///
+/// Symbols are accepted in either case, and [`DecodeError::InvalidInputLength`] is
+/// returned for an empty geohash or one longer than [`MAX_PRECISION`].
+pub fn bounding_box(hash: impl AsRef<[u8]>) -> Result<BoundingBox, DecodeError> {
+    let hash = hash.as_ref();
+    if hash.is_empty() || hash.len() > MAX_PRECISION {
+        return Err(DecodeError::InvalidInputLength);
+    }
+
+    let mut bbox = BoundingBox::WORLD;
+    let mut is_lon = true;
+
+    for (i, &symbol) in hash.iter().enumerate() {
+        let value = match Alphabet::GEOHASH.value_of(symbol) {
+            Some(value) => value,
+            None => return Err(DecodeError::InvalidByte(i, symbol)),
+        };
+
+        for bit in (0..5).rev() {
+            let upper = value >> bit & 1 == 1;
+            if is_lon {
+                halve(&mut bbox.min_lon, &mut bbox.max_lon, upper);
+            } else {
+                halve(&mut bbox.min_lat, &mut bbox.max_lat, upper);
+            }
+            is_lon = !is_lon;
+        }
+    }
+
+    Ok(bbox)
+}
+
+/// Returns the geohash of the same precision next to the given one, wrapping around the
+/// antimeridian, or `None` if the neighbor would lie beyond one of the poles.
+/// ```
+/// use base32::geohash::{self, Direction};
+///
+/// assert_eq!(geohash::neighbor("ezs42", Direction::North).unwrap().unwrap(), "ezs48");
+/// assert_eq!(geohash::neighbor("zzzzz", Direction::East).unwrap().unwrap(), "bpbpb");
+/// assert_eq!(geohash::neighbor("zzzzz", Direction::North).unwrap(), None);
+/// ```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn neighbor(
+    hash: impl AsRef<[u8]>,
+    direction: Direction,
+) -> Result<Option<String>, DecodeError> {
+    let hash = hash.as_ref();
+    let bbox = bounding_box(hash)?;
+    let (lat, lon) = bbox.center();
+    let (north, east) = direction.offset();
+
+    let lat = lat + north * (bbox.max_lat - bbox.min_lat);
+    if !(-90.0..=90.0).contains(&lat) {
+        return Ok(None);
+    }
+
+    let mut lon = lon + east * (bbox.max_lon - bbox.min_lon);
+    if lon > 180.0 {
+        lon -= 360.0;
+    } else if lon < -180.0 {
+        lon += 360.0;
+    }
+
+    let hash = encode(lat, lon, hash.len()).expect("Neighbor must be within range");
+    Ok(Some(hash))
+}
+
+/// Returns the geohashes of all eight neighbors, in the order of [`Direction::ALL`].
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn neighbors(hash: impl AsRef<[u8]>) -> Result<[Option<String>; 8], DecodeError> {
+    let hash = hash.as_ref();
+    let mut neighbors: [Option<String>; 8] = Default::default();
+
+    for (neighbor, &direction) in neighbors.iter_mut().zip(&Direction::ALL) {
+        *neighbor = self::neighbor(hash, direction)?;
+    }
+
+    Ok(neighbors)
+}
+
+fn check_precision(precision: usize) -> Result<(), CoordinateError> {
+    match precision {
+        1..=MAX_PRECISION => Ok(()),
+        _ => Err(CoordinateError::InvalidPrecision(precision)),
+    }
+}
+
+/// Shrinks the range to either its upper or lower half.
+fn halve(min: &mut f64, max: &mut f64, upper: bool) {
+    let mid = (*min + *max) / 2.0;
+    if upper {
+        *min = mid;
+    } else {
+        *max = mid;
+    }
+}
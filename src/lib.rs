@@ -17,6 +17,8 @@
 //! Encoding & decoding with the respective `to_slice` methods will panic if the
 //! provided output slice is too small to handle the input, this will not happen
 //! if the accompanying size translation methods are used to allocate it properly.
+//! The `try_` variants of these methods check the length of the output slice first,
+//! and return an error instead of panicking.
 #![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(
     missing_docs,
@@ -30,13 +32,32 @@
     warnings,
     rust_2018_idioms
 )]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "simd"), forbid(unsafe_code))]
+#![cfg_attr(feature = "simd", deny(unsafe_code))]
 
+mod alphabet;
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub mod bech32;
+mod check;
 mod decode;
+pub mod display;
 mod encode;
+pub mod geohash;
+#[cfg(any(feature = "std", test))]
+pub mod read;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
 mod tables;
+#[cfg(any(feature = "std", test))]
+pub mod write;
 
-pub use decode::DecodeError;
+#[cfg(feature = "pair-decode")]
+pub use alphabet::PairDecodeTable;
+pub use alphabet::{Alphabet, Case, PairEncodeTable};
+pub use decode::{DecodeError, PaddingMode};
+pub use encode::{EncodeError, LineEnding};
 
 #[cfg(all(feature = "alloc", not(any(feature = "std", test))))]
 extern crate alloc;
@@ -51,46 +72,352 @@ pub use crate::encode::encode;
 #[cfg(test)]
 mod tests;
 
+/// An encoding specification.
 #[derive(Copy, Clone, Debug)]
-enum Alphabet {
-    Standard,
-    ExtendedHex,
-    Crockford,
-    ZBase32,
+pub struct Encoding {
+    alpha: Alphabet,
+    pad: Option<u8>,
+    ignore: ByteSet,
+    canonical: bool,
+    padding: PaddingMode,
+    wrap: Option<(usize, LineEnding)>,
+    group: Option<(usize, u8)>,
+    pair_encode: Option<&'static PairEncodeTable>,
+    #[cfg(feature = "pair-decode")]
+    pair_decode: Option<&'static PairDecodeTable>,
 }
 
-impl Alphabet {
-    const fn encode_table(self) -> &'static [u8; 32] {
-        match self {
-            Alphabet::Standard => tables::ENCODE_STD,
-            Alphabet::ExtendedHex => tables::ENCODE_HEX,
-            Alphabet::Crockford => tables::ENCODE_CROCKFORD,
-            Alphabet::ZBase32 => tables::ENCODE_ZBASE32,
+impl Encoding {
+    /// Creates an encoding from an alphabet and an optional padding byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padding byte is also a symbol of the alphabet.
+    #[inline]
+    pub const fn new(alphabet: Alphabet, pad: Option<u8>) -> Encoding {
+        Encoding {
+            alpha: alphabet,
+            pad: None,
+            ignore: ByteSet::EMPTY,
+            canonical: false,
+            padding: PaddingMode::Optional,
+            wrap: None,
+            group: None,
+            pair_encode: None,
+            #[cfg(feature = "pair-decode")]
+            pair_decode: None,
+        }
+        .with_padding(pad)
+    }
+
+    /// Changes or disables padding
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padding byte is also a symbol of the alphabet or ignored while decoding.
+    #[inline]
+    pub const fn with_padding(self, pad: Option<u8>) -> Encoding {
+        if let Some(pad) = pad {
+            if self.alpha.decode_table()[pad as usize] != tables::INVALID_BYTE {
+                panic!("padding byte must not be a symbol of the alphabet");
+            }
+            if self.ignore.contains(pad) {
+                panic!("padding byte must not be ignored");
+            }
+        }
+
+        Encoding { pad, ..self }
+    }
+
+    /// Skips the given bytes wherever they occur in the input when decoding. Crockford's
+    /// specification allows hyphens to be inserted anywhere for readability, so those
+    /// can be accepted with `CROCKFORD.with_ignored(b"-")`.
+    /// ```
+    /// const CROCKFORD: base32::Encoding = base32::CROCKFORD.with_ignored(b"-");
+    ///
+    /// assert_eq!(CROCKFORD.decode("CSQP-YRK1-E8").unwrap(), b"foobar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the bytes are a symbol of the alphabet or the padding byte.
+    #[inline]
+    pub const fn with_ignored(mut self, bytes: &[u8]) -> Encoding {
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            if self.alpha.decode_table()[byte as usize] != tables::INVALID_BYTE {
+                panic!("ignored bytes must not be symbols of the alphabet");
+            }
+            if let Some(pad) = self.pad {
+                if pad == byte {
+                    panic!("ignored bytes must not be the padding byte");
+                }
+            }
+            self.ignore = self.ignore.insert(byte);
+            i += 1;
+        }
+
+        self
+    }
+
+    /// Skips ASCII whitespace wherever it occurs in the input when decoding, so that
+    /// line-wrapped or indented data can be decoded as is. This is the same as calling
+    /// [`with_ignored`](Encoding::with_ignored) with space, tab, line feed, form feed
+    /// and carriage return.
+    /// ```
+    /// const STANDARD: base32::Encoding = base32::STANDARD.with_ignored_whitespace();
+    ///
+    /// assert_eq!(STANDARD.decode("MZXW6\r\n  YTBOI======\n").unwrap(), b"foobar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the whitespace is a symbol of the alphabet or the padding byte.
+    #[inline]
+    pub const fn with_ignored_whitespace(self) -> Encoding {
+        self.with_ignored(b" \t\n\x0C\r")
+    }
+
+    /// Breaks encoded output into lines of `width` symbols, separated by the given line
+    /// ending. The last line doesn't get a line ending. Line endings are skipped while
+    /// decoding, as if they were passed to [`with_ignored`](Encoding::with_ignored).
+    /// ```
+    /// use base32::LineEnding;
+    ///
+    /// const WRAPPED: base32::Encoding = base32::STANDARD.with_line_wrap(8, LineEnding::Lf);
+    ///
+    /// assert_eq!(WRAPPED.encode("foobar"), "MZXW6YTB\nOI======");
+    /// assert_eq!(WRAPPED.decode("MZXW6YTB\nOI======").unwrap(), b"foobar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width is zero, or if the line ending is a symbol of
+    /// the alphabet or the padding byte.
+    #[inline]
+    pub const fn with_line_wrap(self, width: usize, ending: LineEnding) -> Encoding {
+        if width == 0 {
+            panic!("line width must not be zero");
+        }
+
+        Encoding {
+            wrap: Some((width, ending)),
+            ..self.with_ignored(ending.as_bytes())
+        }
+    }
+
+    /// Inserts the separator in between every group of `size` symbols to make encoded output
+    /// easier for humans to read, while the separator is skipped when decoding as if it were
+    /// passed to [`with_ignored`](Encoding::with_ignored). Combined with
+    /// [line wrapping](Encoding::with_line_wrap), line endings take the place of separators
+    /// wherever both would go.
+    /// ```
+    /// const CROCKFORD: base32::Encoding = base32::CROCKFORD.with_grouping(4, b'-');
+    /// const ZBASE32: base32::Encoding = base32::ZBASE32.with_grouping(4, b' ');
+    ///
+    /// assert_eq!(CROCKFORD.encode("foobar"), "CSQP-YRK1-E8");
+    /// assert_eq!(CROCKFORD.decode("CSQP-YRK1-E8").unwrap(), b"foobar");
+    /// assert_eq!(ZBASE32.encode([0xF0, 0xBF, 0xC7]), "6n9h q");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is zero, or if the separator is a symbol of
+    /// the alphabet or the padding byte.
+    #[inline]
+    pub const fn with_grouping(self, size: usize, separator: u8) -> Encoding {
+        if size == 0 {
+            panic!("group size must not be zero");
+        }
+
+        Encoding {
+            group: Some((size, separator)),
+            ..self.with_ignored(&[separator])
+        }
+    }
+
+    /// Enables or disables canonical decoding, which rejects input where the last symbol has
+    /// non-zero bits that don't make up a whole byte. Without it, both `MY` and `MZ` decode to
+    /// `f` with the standard alphabet, even though only `MY` is ever produced when encoding.
+    /// ```
+    /// const STRICT: base32::Encoding = base32::STANDARD.with_canonical_decoding(true);
+    ///
+    /// assert!(STRICT.decode("MY======").is_ok());
+    /// assert!(STRICT.decode("MZ======").is_err());
+    /// ```
+    #[inline]
+    pub const fn with_canonical_decoding(self, canonical: bool) -> Encoding {
+        Encoding { canonical, ..self }
+    }
+
+    /// Changes how padding is treated while decoding, padding is optional by default.
+    /// ```
+    /// use base32::PaddingMode;
+    ///
+    /// const REQUIRED: base32::Encoding = base32::STANDARD.with_padding_mode(PaddingMode::Required);
+    /// const FORBIDDEN: base32::Encoding = base32::STANDARD.with_padding_mode(PaddingMode::Forbidden);
+    ///
+    /// assert!(REQUIRED.decode("MZXW6===").is_ok());
+    /// assert!(REQUIRED.decode("MZXW6").is_err());
+    /// assert!(FORBIDDEN.decode("MZXW6").is_ok());
+    /// assert!(FORBIDDEN.decode("MZXW6===").is_err());
+    /// ```
+    #[inline]
+    pub const fn with_padding_mode(self, padding: PaddingMode) -> Encoding {
+        Encoding { padding, ..self }
+    }
+
+    /// Changes the case that letters are encoded in. Decoding accepts either case unless
+    /// [case sensitive decoding](Encoding::with_case_sensitive_decoding) is enabled.
+    /// ```
+    /// use base32::Case;
+    ///
+    /// const LOWER: base32::Encoding = base32::STANDARD.with_case(Case::Lower);
+    ///
+    /// assert_eq!(LOWER.encode("foobar"), "mzxw6ytboi======");
+    /// assert_eq!(LOWER.decode("MZXW6YTBOI======").unwrap(), b"foobar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alphabet uses both cases of the same letter, or if the padding
+    /// byte or any of the ignored bytes become symbols of the alphabet.
+    #[inline]
+    pub const fn with_case(self, case: Case) -> Encoding {
+        Encoding {
+            alpha: self.alpha.with_case(case),
+            ..self
+        }
+        .checked()
+    }
+
+    /// Enables or disables case sensitive decoding, which rejects letters that aren't in the
+    /// case they're encoded in.
+    /// ```
+    /// use base32::Case;
+    ///
+    /// const STRICT: base32::Encoding = base32::STANDARD
+    ///     .with_case(Case::Lower)
+    ///     .with_case_sensitive_decoding(true);
+    ///
+    /// assert!(STRICT.decode("mzxw6===").is_ok());
+    /// assert!(STRICT.decode("MZXW6===").is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padding byte or any of the ignored bytes become symbols of the alphabet.
+    #[inline]
+    pub const fn with_case_sensitive_decoding(self, sensitive: bool) -> Encoding {
+        Encoding {
+            alpha: self.alpha.with_case_sensitive_decoding(sensitive),
+            ..self
+        }
+        .checked()
+    }
+
+    /// Encodes two symbols per lookup using the table, which must have been built for the
+    /// alphabet of this encoding. Changing the case of the alphabet afterwards goes back to
+    /// encoding one symbol at a time, unless the table still matches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table was built for a different alphabet.
+    #[inline]
+    pub const fn with_pair_encode_table(mut self, table: &'static PairEncodeTable) -> Encoding {
+        if !table.matches(&self.alpha) {
+            panic!("pair encode table must be built for the alphabet of the encoding");
         }
+
+        self.pair_encode = Some(table);
+        self
     }
 
-    const fn decode_table(self) -> &'static [u8; 256] {
-        match self {
-            Alphabet::Standard => tables::DECODE_STD,
-            Alphabet::ExtendedHex => tables::DECODE_HEX,
-            Alphabet::Crockford => tables::DECODE_CROCKFORD,
-            Alphabet::ZBase32 => tables::DECODE_ZBASE32,
+    /// Decodes two symbols per lookup using the table, which must have been built for the
+    /// alphabet of this encoding. Changing the case of the alphabet afterwards goes back to
+    /// decoding one symbol at a time, unless the table still matches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table was built for a different alphabet.
+    #[cfg(feature = "pair-decode")]
+    #[inline]
+    pub const fn with_pair_decode_table(mut self, table: &'static PairDecodeTable) -> Encoding {
+        if !table.matches(&self.alpha) {
+            panic!("pair decode table must be built for the alphabet of the encoding");
         }
+
+        self.pair_decode = Some(table);
+        self
+    }
+
+    /// Returns the alphabet used by this encoding.
+    #[inline]
+    pub const fn alphabet(&self) -> &Alphabet {
+        &self.alpha
+    }
+
+    /// Returns the padding byte used by this encoding, if any.
+    #[inline]
+    pub const fn padding(&self) -> Option<u8> {
+        self.pad
+    }
+
+    /// Checks that the padding byte and ignored bytes are still
+    /// valid after the alphabet has been changed.
+    const fn checked(self) -> Encoding {
+        let mut byte = 0;
+        while byte < 256 {
+            if self.ignore.contains(byte as u8)
+                && self.alpha.decode_table()[byte] != tables::INVALID_BYTE
+            {
+                panic!("ignored bytes must not be symbols of the alphabet");
+            }
+            byte += 1;
+        }
+
+        let pair_encode = match self.pair_encode {
+            Some(table) if table.matches(&self.alpha) => Some(table),
+            _ => None,
+        };
+        #[cfg(feature = "pair-decode")]
+        let pair_decode = match self.pair_decode {
+            Some(table) if table.matches(&self.alpha) => Some(table),
+            _ => None,
+        };
+
+        Encoding {
+            pair_encode,
+            #[cfg(feature = "pair-decode")]
+            pair_decode,
+            ..self
+        }
+        .with_padding(self.pad)
     }
 }
 
-/// An encoding specification.
+/// A set of bytes, used to keep track of which bytes to skip while decoding.
 #[derive(Copy, Clone, Debug)]
-pub struct Encoding {
-    alpha: Alphabet,
-    pad: Option<u8>,
-}
+struct ByteSet([u64; 4]);
+
+impl ByteSet {
+    const EMPTY: ByteSet = ByteSet([0; 4]);
 
-impl Encoding {
-    /// Changes or disables padding
     #[inline]
-    pub const fn with_padding(self, pad: Option<u8>) -> Encoding {
-        Encoding { pad, ..self }
+    const fn insert(mut self, byte: u8) -> ByteSet {
+        self.0[(byte >> 6) as usize] |= 1 << (byte & 0x3F);
+        self
+    }
+
+    #[inline]
+    const fn contains(&self, byte: u8) -> bool {
+        self.0[(byte >> 6) as usize] & 1 << (byte & 0x3F) != 0
+    }
+
+    #[inline]
+    const fn is_empty(&self) -> bool {
+        self.0[0] | self.0[1] | self.0[2] | self.0[3] == 0
     }
 }
 
@@ -108,8 +435,16 @@ impl Default for Encoding {
 /// assert_eq!(base32::STANDARD.encode("foobar"), "MZXW6YTBOI======");
 /// ```
 pub const STANDARD: Encoding = Encoding {
-    alpha: Alphabet::Standard,
+    alpha: Alphabet::STANDARD,
     pad: Some(b'='),
+    ignore: ByteSet::EMPTY,
+    canonical: false,
+    padding: PaddingMode::Optional,
+    wrap: None,
+    group: None,
+    pair_encode: Some(tables::PAIR_ENCODE_STD),
+    #[cfg(feature = "pair-decode")]
+    pair_decode: Some(tables::PAIR_DECODE_STD),
 };
 
 /// The extended hex character set defined in RFC4648. It is based
@@ -118,21 +453,38 @@ pub const STANDARD: Encoding = Encoding {
 /// assert_eq!(base32::EXTENDED_HEX.encode("foobar"), "CPNMUOJ1E8======");
 /// ```
 pub const EXTENDED_HEX: Encoding = Encoding {
-    alpha: Alphabet::ExtendedHex,
+    alpha: Alphabet::EXTENDED_HEX,
     pad: Some(b'='),
+    ignore: ByteSet::EMPTY,
+    canonical: false,
+    padding: PaddingMode::Optional,
+    wrap: None,
+    group: None,
+    pair_encode: Some(tables::PAIR_ENCODE_HEX),
+    #[cfg(feature = "pair-decode")]
+    pair_decode: Some(tables::PAIR_DECODE_HEX),
 };
 
 /// An alternative design for base32 created by Douglas Crockford. It excludes the letters
 /// I, L, and O to avoid confusion with digits. It also excludes the letter U to reduce the
-/// likelihood of accidental obscenity. Checksums are currently not supported by this library.
+/// likelihood of accidental obscenity. The optional check symbol is supported through
+/// [`Encoding::encode_with_check`] and [`Encoding::decode_with_check`].
 ///
 /// [https://crockford.com/base32.html](https://crockford.com/base32.html)
 /// ```
 /// assert_eq!(base32::CROCKFORD.encode("foobar"), "CSQPYRK1E8");
 /// ```
 pub const CROCKFORD: Encoding = Encoding {
-    alpha: Alphabet::Crockford,
+    alpha: Alphabet::CROCKFORD,
     pad: None,
+    ignore: ByteSet::EMPTY,
+    canonical: false,
+    padding: PaddingMode::Optional,
+    wrap: None,
+    group: None,
+    pair_encode: Some(tables::PAIR_ENCODE_CROCKFORD),
+    #[cfg(feature = "pair-decode")]
+    pair_decode: Some(tables::PAIR_DECODE_CROCKFORD),
 };
 
 /// z-base-32 is a base32 encoding designed to be easier for human use and more compact. It
@@ -144,6 +496,14 @@ pub const CROCKFORD: Encoding = Encoding {
 /// assert_eq!(base32::ZBASE32.encode("foobar"), "c3zs6aubqe");
 /// ```
 pub const ZBASE32: Encoding = Encoding {
-    alpha: Alphabet::ZBase32,
+    alpha: Alphabet::ZBASE32,
     pad: None,
+    ignore: ByteSet::EMPTY,
+    canonical: false,
+    padding: PaddingMode::Optional,
+    wrap: None,
+    group: None,
+    pair_encode: Some(tables::PAIR_ENCODE_ZBASE32),
+    #[cfg(feature = "pair-decode")]
+    pair_decode: Some(tables::PAIR_DECODE_ZBASE32),
 };
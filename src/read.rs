@@ -0,0 +1,235 @@
+//! Streaming decoding through [`std::io::Read`].
+use core::{cmp, fmt};
+use std::io::{self, Read};
+
+use crate::{DecodeError, Encoding};
+
+const INPUT_CHUNK_LEN: usize = 8;
+const OUTPUT_CHUNK_LEN: usize = 5;
+
+// Must be a multiple of INPUT_CHUNK_LEN
+const INPUT_BUF_LEN: usize = 1024;
+// Room for a single chunk carried over from the previous read, as well as everything in the input buffer
+const OUTPUT_BUF_LEN: usize = OUTPUT_CHUNK_LEN + INPUT_BUF_LEN / INPUT_CHUNK_LEN * OUTPUT_CHUNK_LEN;
+
+/// Decodes data read from the wrapped reader.
+///
+/// Symbols that don't make up a whole chunk of eight are carried over between reads, and
+/// decoded along with any padding once the wrapped reader reaches EOF. Decoding errors are
+/// returned as [`io::ErrorKind::InvalidData`] wrapping a [`DecodeError`], with indexes
+/// counted from the start of the stream.
+/// ```
+/// use std::io::Read;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut reader = base32::read::DecoderReader::new(&b"MZXW6YTBOI======"[..], base32::STANDARD);
+///     let mut decoded = Vec::new();
+///     reader.read_to_end(&mut decoded)?;
+///
+///     assert_eq!(decoded, b"foobar");
+///     Ok(())
+/// }
+/// ```
+pub struct DecoderReader<R: Read> {
+    encoding: Encoding,
+    delegate: R,
+    input: [u8; INPUT_BUF_LEN],
+    // offset of the start of the input buffer in the stream
+    offset: usize,
+    chunk: [u8; INPUT_CHUNK_LEN],
+    chunk_offsets: [usize; INPUT_CHUNK_LEN],
+    chunk_len: usize,
+    output: [u8; OUTPUT_BUF_LEN],
+    output_start: usize,
+    output_len: usize,
+    padded: bool,
+    eof: bool,
+}
+
+impl<R: Read> DecoderReader<R> {
+    /// Creates a new reader that decodes data read from
+    /// the delegate according to the given configuration.
+    pub fn new(delegate: R, encoding: Encoding) -> DecoderReader<R> {
+        DecoderReader {
+            encoding,
+            delegate,
+            input: [0; INPUT_BUF_LEN],
+            offset: 0,
+            chunk: [0; INPUT_CHUNK_LEN],
+            chunk_offsets: [0; INPUT_CHUNK_LEN],
+            chunk_len: 0,
+            output: [0; OUTPUT_BUF_LEN],
+            output_start: 0,
+            output_len: 0,
+            padded: false,
+            eof: false,
+        }
+    }
+
+    /// Returns the wrapped reader, discarding any data that has been read but not yet returned.
+    pub fn into_inner(self) -> R {
+        self.delegate
+    }
+
+    /// Decodes everything in the first `len` bytes of the input buffer, carrying over any
+    /// symbols that don't make up a whole chunk.
+    fn decode_input(&mut self, len: usize) -> Result<(), DecodeError> {
+        let mut input_index = 0;
+
+        if self.chunk_len > 0 {
+            while self.chunk_len < INPUT_CHUNK_LEN && input_index < len {
+                self.push_symbol(input_index)?;
+                input_index += 1;
+            }
+
+            if self.chunk_len < INPUT_CHUNK_LEN {
+                return Ok(());
+            }
+
+            self.decode_chunk()?;
+        }
+
+        let symbols = self.input[input_index..len]
+            .iter()
+            .filter(|b| !self.encoding.ignore.contains(**b))
+            .count();
+        let mut end = len;
+
+        if symbols % INPUT_CHUNK_LEN != 0 {
+            // find the end of the last whole chunk
+            let mut remaining = symbols % INPUT_CHUNK_LEN;
+            while end > input_index
+                && (remaining > 0 || self.encoding.ignore.contains(self.input[end - 1]))
+            {
+                end -= 1;
+                if !self.encoding.ignore.contains(self.input[end]) {
+                    remaining -= 1;
+                }
+            }
+        }
+
+        if symbols >= INPUT_CHUNK_LEN {
+            if self.padded {
+                return Err(self.symbol_after_padding(input_index));
+            }
+
+            let offset = self.offset + input_index;
+            let written = self
+                .encoding
+                .decode_to_slice(
+                    &mut self.output[self.output_len..],
+                    &self.input[input_index..end],
+                )
+                .map_err(|e| map_index(e, |i| offset + i))?;
+
+            self.padded = written < symbols / INPUT_CHUNK_LEN * OUTPUT_CHUNK_LEN;
+            self.output_len += written;
+        }
+
+        for i in end..len {
+            self.push_symbol(i)?;
+        }
+
+        Ok(())
+    }
+
+    /// Carries over the symbol at the given index in the input buffer, if it isn't ignored.
+    fn push_symbol(&mut self, index: usize) -> Result<(), DecodeError> {
+        let symbol = self.input[index];
+
+        if !self.encoding.ignore.contains(symbol) {
+            if self.padded {
+                return Err(self.symbol_after_padding(index));
+            }
+
+            self.chunk[self.chunk_len] = symbol;
+            self.chunk_offsets[self.chunk_len] = self.offset + index;
+            self.chunk_len += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the symbols that have been carried over, which may only be a partial chunk at EOF.
+    fn decode_chunk(&mut self) -> Result<(), DecodeError> {
+        let chunk_offsets = self.chunk_offsets;
+        let written = self
+            .encoding
+            .decode_to_slice(
+                &mut self.output[self.output_len..],
+                &self.chunk[..self.chunk_len],
+            )
+            .map_err(|e| map_index(e, |i| chunk_offsets[i]))?;
+
+        self.padded = self.chunk_len == INPUT_CHUNK_LEN && written < OUTPUT_CHUNK_LEN;
+        self.output_len += written;
+        self.chunk_len = 0;
+
+        Ok(())
+    }
+
+    fn symbol_after_padding(&self, index: usize) -> DecodeError {
+        let index = (index..)
+            .find(|i| !self.encoding.ignore.contains(self.input[*i]))
+            .expect("Input must contain a symbol");
+
+        DecodeError::InvalidByte(self.offset + index, self.input[index])
+    }
+}
+
+impl<R: Read> Read for DecoderReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.output_start == self.output_len {
+            if self.eof {
+                return Ok(0);
+            }
+
+            self.output_start = 0;
+            self.output_len = 0;
+
+            let len = self.delegate.read(&mut self.input)?;
+            let result = if len == 0 {
+                self.eof = true;
+                self.decode_chunk()
+            } else {
+                self.decode_input(len)
+            };
+
+            self.offset += len;
+            result.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+
+        let len = cmp::min(buf.len(), self.output_len - self.output_start);
+        buf[..len].copy_from_slice(&self.output[self.output_start..self.output_start + len]);
+        self.output_start += len;
+
+        Ok(len)
+    }
+}
+
+impl<R: Read> fmt::Debug for DecoderReader<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DecoderReader")
+            .field("encoding", &self.encoding)
+            .field("offset", &self.offset)
+            .field("chunk", &&self.chunk[..self.chunk_len])
+            .field("output", &&self.output[self.output_start..self.output_len])
+            .field("padded", &self.padded)
+            .field("eof", &self.eof)
+            .finish()
+    }
+}
+
+fn map_index(error: DecodeError, f: impl FnOnce(usize) -> usize) -> DecodeError {
+    match error {
+        DecodeError::InvalidByte(i, b) => DecodeError::InvalidByte(f(i), b),
+        DecodeError::InvalidLastSymbol(i, b) => DecodeError::InvalidLastSymbol(f(i), b),
+        DecodeError::InvalidPadding(i) => DecodeError::InvalidPadding(f(i)),
+        DecodeError::UnexpectedPadding(i) => DecodeError::UnexpectedPadding(f(i)),
+        e => e,
+    }
+}
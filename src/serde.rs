@@ -0,0 +1,117 @@
+//! Serializing and deserializing bytes as base32 strings with serde.
+//!
+//! There's a module for each of the presets to use with `#[serde(with = "...")]`, while
+//! [`serialize_with`] and [`deserialize_with`] work with any other [`Encoding`]. Fields can
+//! be anything that dereferences to bytes when serializing, and anything that can be built
+//! from a `Vec<u8>` when deserializing, such as `Vec<u8>` itself or `[u8; N]`.
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Key {
+//!     #[serde(with = "base32::serde::standard")]
+//!     id: Vec<u8>,
+//!     #[serde(with = "base32::serde::crockford")]
+//!     secret: [u8; 5],
+//! }
+//!
+//! let key = Key { id: b"foobar".to_vec(), secret: *b"hello" };
+//! let json = serde_json::to_string(&key).unwrap();
+//! assert_eq!(json, r#"{"id":"MZXW6YTBOI======","secret":"D1JPRV3F"}"#);
+//! ```
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::Serializer;
+
+use crate::Encoding;
+
+/// Serializes the bytes as a string encoded according to the configuration.
+pub fn serialize_with<T, S>(encoding: &Encoding, data: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+    S: Serializer,
+{
+    serializer.collect_str(&encoding.display(data.as_ref()))
+}
+
+/// Deserializes bytes from a string encoded according to the configuration. Decoding
+/// errors are turned into custom errors of the deserializer, and bytes that can't be
+/// converted into the target type into invalid length errors.
+pub fn deserialize_with<'de, T, D>(encoding: &Encoding, deserializer: D) -> Result<T, D::Error>
+where
+    T: TryFrom<Vec<u8>>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(Base32Visitor {
+        encoding,
+        marker: PhantomData,
+    })
+}
+
+struct Base32Visitor<'a, T> {
+    encoding: &'a Encoding,
+    marker: PhantomData<T>,
+}
+
+impl<T: TryFrom<Vec<u8>>> Visitor<'_> for Base32Visitor<'_, T> {
+    type Value = T;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "a base32 encoded string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        let decoded = self.encoding.decode(v).map_err(E::custom)?;
+        let len = decoded.len();
+
+        T::try_from(decoded).map_err(|_| E::invalid_length(len, &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        let v = core::str::from_utf8(v)
+            .map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))?;
+
+        self.visit_str(v)
+    }
+}
+
+macro_rules! preset_module {
+    ($name:ident, $encoding:ident) => {
+        #[doc = concat!("Serializes and deserializes using [`", stringify!($encoding), "`].")]
+        ///
+        #[doc = concat!("[`", stringify!($encoding), "`]: crate::", stringify!($encoding))]
+        pub mod $name {
+            use alloc::vec::Vec;
+            use core::convert::TryFrom;
+
+            use ::serde::{Deserializer, Serializer};
+
+            /// Serializes the bytes as a base32 encoded string.
+            pub fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: AsRef<[u8]> + ?Sized,
+                S: Serializer,
+            {
+                super::serialize_with(&crate::$encoding, data, serializer)
+            }
+
+            /// Deserializes bytes from a base32 encoded string.
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+            where
+                T: TryFrom<Vec<u8>>,
+                D: Deserializer<'de>,
+            {
+                super::deserialize_with(&crate::$encoding, deserializer)
+            }
+        }
+    };
+}
+
+preset_module!(standard, STANDARD);
+preset_module!(extended_hex, EXTENDED_HEX);
+preset_module!(crockford, CROCKFORD);
+preset_module!(zbase32, ZBASE32);
@@ -0,0 +1,304 @@
+//! Vectorised encoding and decoding for x86_64, using AVX2 or SSSE3 depending on what the
+//! CPU supports.
+//!
+//! When encoding, every group of five input bytes is spread out over eight 16-bit lanes,
+//! each holding the two bytes that a symbol's bits are found in. The lanes are then shifted
+//! right by a different amount each, by multiplying and keeping the high half, to line up
+//! the five bits in the low end. Symbols are looked up in the encode table with two 16 byte
+//! shuffles, one for the lower and one for the upper half of the table, so any alphabet works.
+//!
+//! When decoding, symbols are looked up in the first half of the decode table, which covers
+//! all of ASCII, as eight rows of 16 values picked by the high nibble of each symbol. Invalid
+//! symbols either map to [`INVALID_BYTE`] or aren't ASCII, so both have their high bit set,
+//! which is checked for the whole step at once. The values are then joined into pairs, fours
+//! and finally eights, which make up five bytes of output. Steps with invalid symbols are left
+//! to the scalar decoder to find the exact error.
+#![allow(unsafe_code)]
+
+use core::arch::x86_64::*;
+
+#[cfg(doc)]
+use crate::tables::INVALID_BYTE;
+
+// Each encode step loads 16 bytes of input, of which only the first 10 are encoded
+const INPUT_STEP_LEN: usize = 10;
+const INPUT_LOAD_LEN: usize = 16;
+const OUTPUT_STEP_LEN: usize = 16;
+
+// Each decode step decodes 16 symbols into 10 bytes, but stores 16
+const DECODE_INPUT_STEP_LEN: usize = 16;
+const DECODE_OUTPUT_STEP_LEN: usize = 10;
+const DECODE_OUTPUT_STORE_LEN: usize = 16;
+
+/// Encodes as much of the input as possible in whole steps, returning how many bytes were
+/// read from the input and written to the output. The rest is left to the scalar encoder.
+#[inline]
+pub(crate) fn encode(encode_table: &[u8; 32], output: &mut [u8], input: &[u8]) -> (usize, usize) {
+    if input.len() < INPUT_LOAD_LEN {
+        return (0, 0);
+    }
+
+    if is_x86_feature_detected!("avx2") {
+        // Safety: the CPU supports AVX2
+        unsafe { encode_avx2(encode_table, output, input) }
+    } else if is_x86_feature_detected!("ssse3") {
+        // Safety: the CPU supports SSSE3
+        unsafe { encode_ssse3(encode_table, output, input, 0, 0) }
+    } else {
+        (0, 0)
+    }
+}
+
+/// Places the two bytes that each symbol's bits are in into a 16-bit lane, with
+/// the first byte in the high half. The last symbol only needs a single byte.
+#[rustfmt::skip]
+const SPREAD: [i8; 16] = [
+    1, 0, 1, 0, 2, 1, 2, 1, 3, 2, 4, 3, 4, 3, -1, 4,
+];
+
+/// Multiplying by these and keeping the high half shifts each lane right by 11, 6, 9, 4,
+/// 7, 10, 5 and 8 bits respectively.
+const SHIFT: [i16; 8] = [
+    1 << 5,
+    1 << 10,
+    1 << 7,
+    1 << 12,
+    1 << 9,
+    1 << 6,
+    1 << 11,
+    1 << 8,
+];
+
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_ssse3(
+    encode_table: &[u8; 32],
+    output: &mut [u8],
+    input: &[u8],
+    mut input_index: usize,
+    mut output_index: usize,
+) -> (usize, usize) {
+    let spread_first = _mm_loadu_si128(SPREAD.as_ptr() as *const __m128i);
+    let spread_second = _mm_add_epi8(spread_first, _mm_set1_epi8(5));
+    let shift = _mm_loadu_si128(SHIFT.as_ptr() as *const __m128i);
+    let table_low = _mm_loadu_si128(encode_table.as_ptr() as *const __m128i);
+    let table_high = _mm_loadu_si128(encode_table[16..].as_ptr() as *const __m128i);
+
+    while input.len() - input_index >= INPUT_LOAD_LEN
+        && output.len() - output_index >= OUTPUT_STEP_LEN
+    {
+        let bytes = _mm_loadu_si128(input[input_index..].as_ptr() as *const __m128i);
+
+        let first = _mm_shuffle_epi8(bytes, spread_first);
+        // the last lane picks up byte 4 instead of nothing here, which is shifted out anyway
+        let second = _mm_shuffle_epi8(bytes, spread_second);
+        let quintets = _mm_packus_epi16(
+            _mm_and_si128(_mm_mulhi_epu16(first, shift), _mm_set1_epi16(0x1F)),
+            _mm_and_si128(_mm_mulhi_epu16(second, shift), _mm_set1_epi16(0x1F)),
+        );
+
+        let high = _mm_cmpgt_epi8(quintets, _mm_set1_epi8(15));
+        let symbols = _mm_or_si128(
+            _mm_and_si128(high, _mm_shuffle_epi8(table_high, quintets)),
+            _mm_andnot_si128(high, _mm_shuffle_epi8(table_low, quintets)),
+        );
+
+        _mm_storeu_si128(output[output_index..].as_mut_ptr() as *mut __m128i, symbols);
+
+        input_index += INPUT_STEP_LEN;
+        output_index += OUTPUT_STEP_LEN;
+    }
+
+    (input_index, output_index)
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn encode_avx2(encode_table: &[u8; 32], output: &mut [u8], input: &[u8]) -> (usize, usize) {
+    let spread_first =
+        _mm256_broadcastsi128_si256(_mm_loadu_si128(SPREAD.as_ptr() as *const __m128i));
+    let spread_second = _mm256_add_epi8(spread_first, _mm256_set1_epi8(5));
+    let shift = _mm256_broadcastsi128_si256(_mm_loadu_si128(SHIFT.as_ptr() as *const __m128i));
+    let table_low =
+        _mm256_broadcastsi128_si256(_mm_loadu_si128(encode_table.as_ptr() as *const __m128i));
+    let table_high = _mm256_broadcastsi128_si256(_mm_loadu_si128(
+        encode_table[16..].as_ptr() as *const __m128i
+    ));
+
+    let mut input_index = 0;
+    let mut output_index = 0;
+
+    // each 128-bit lane encodes its own step, as shuffles can't cross lanes
+    while input.len() - input_index >= INPUT_STEP_LEN + INPUT_LOAD_LEN
+        && output.len() - output_index >= 2 * OUTPUT_STEP_LEN
+    {
+        let bytes = _mm256_inserti128_si256(
+            _mm256_castsi128_si256(_mm_loadu_si128(
+                input[input_index..].as_ptr() as *const __m128i
+            )),
+            _mm_loadu_si128(input[input_index + INPUT_STEP_LEN..].as_ptr() as *const __m128i),
+            1,
+        );
+
+        let first = _mm256_shuffle_epi8(bytes, spread_first);
+        let second = _mm256_shuffle_epi8(bytes, spread_second);
+        let quintets = _mm256_packus_epi16(
+            _mm256_and_si256(_mm256_mulhi_epu16(first, shift), _mm256_set1_epi16(0x1F)),
+            _mm256_and_si256(_mm256_mulhi_epu16(second, shift), _mm256_set1_epi16(0x1F)),
+        );
+
+        let high = _mm256_cmpgt_epi8(quintets, _mm256_set1_epi8(15));
+        let symbols = _mm256_blendv_epi8(
+            _mm256_shuffle_epi8(table_low, quintets),
+            _mm256_shuffle_epi8(table_high, quintets),
+            high,
+        );
+
+        _mm256_storeu_si256(output[output_index..].as_mut_ptr() as *mut __m256i, symbols);
+
+        input_index += 2 * INPUT_STEP_LEN;
+        output_index += 2 * OUTPUT_STEP_LEN;
+    }
+
+    encode_ssse3(encode_table, output, input, input_index, output_index)
+}
+
+/// Decodes as much of the input as possible in whole steps, returning how many bytes were
+/// read from the input and written to the output. Decoding stops at the first step with an
+/// invalid symbol, so that the scalar decoder can take over and report it.
+#[inline]
+pub(crate) fn decode(decode_table: &[u8; 256], output: &mut [u8], input: &[u8]) -> (usize, usize) {
+    if input.len() < DECODE_INPUT_STEP_LEN {
+        return (0, 0);
+    }
+
+    if is_x86_feature_detected!("avx2") {
+        // Safety: the CPU supports AVX2
+        unsafe { decode_avx2(decode_table, output, input) }
+    } else if is_x86_feature_detected!("ssse3") {
+        // Safety: the CPU supports SSSE3
+        unsafe { decode_ssse3(decode_table, output, input, 0, 0) }
+    } else {
+        (0, 0)
+    }
+}
+
+/// Moves the five bytes that each 64-bit lane decodes to into
+/// place, in order, at the start of the 128-bit lane.
+#[rustfmt::skip]
+const GATHER: [i8; 16] = [
+    4, 3, 2, 1, 0, 12, 11, 10, 9, 8, -1, -1, -1, -1, -1, -1,
+];
+
+// The bits of the first four symbols of a lane, once they've been moved up by 20 bits
+const JOINED_MASK: i64 = 0xFF_FFF0_0000;
+
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_ssse3(
+    decode_table: &[u8; 256],
+    output: &mut [u8],
+    input: &[u8],
+    mut input_index: usize,
+    mut output_index: usize,
+) -> (usize, usize) {
+    let mut rows = [_mm_setzero_si128(); 8];
+    for (i, row) in rows.iter_mut().enumerate() {
+        *row = _mm_loadu_si128(decode_table[i * 16..].as_ptr() as *const __m128i);
+    }
+    let gather = _mm_loadu_si128(GATHER.as_ptr() as *const __m128i);
+
+    while input.len() - input_index >= DECODE_INPUT_STEP_LEN
+        && output.len() - output_index >= DECODE_OUTPUT_STORE_LEN
+    {
+        let symbols = _mm_loadu_si128(input[input_index..].as_ptr() as *const __m128i);
+
+        let high_nibbles = _mm_and_si128(_mm_srli_epi16(symbols, 4), _mm_set1_epi8(0x0F));
+        let mut quintets = _mm_setzero_si128();
+        for (i, row) in rows.iter().enumerate() {
+            let in_row = _mm_cmpeq_epi8(high_nibbles, _mm_set1_epi8(i as i8));
+            quintets = _mm_or_si128(
+                quintets,
+                _mm_and_si128(in_row, _mm_shuffle_epi8(*row, symbols)),
+            );
+        }
+
+        if _mm_movemask_epi8(_mm_or_si128(quintets, symbols)) != 0 {
+            break;
+        }
+
+        let pairs = _mm_maddubs_epi16(quintets, _mm_set1_epi16(0x0120));
+        let fours = _mm_madd_epi16(pairs, _mm_set1_epi32(0x0001_0400));
+        let joined = _mm_or_si128(
+            _mm_srli_epi64(fours, 32),
+            _mm_and_si128(_mm_slli_epi64(fours, 20), _mm_set1_epi64x(JOINED_MASK)),
+        );
+
+        _mm_storeu_si128(
+            output[output_index..].as_mut_ptr() as *mut __m128i,
+            _mm_shuffle_epi8(joined, gather),
+        );
+
+        input_index += DECODE_INPUT_STEP_LEN;
+        output_index += DECODE_OUTPUT_STEP_LEN;
+    }
+
+    (input_index, output_index)
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn decode_avx2(decode_table: &[u8; 256], output: &mut [u8], input: &[u8]) -> (usize, usize) {
+    let mut rows = [_mm256_setzero_si256(); 8];
+    for (i, row) in rows.iter_mut().enumerate() {
+        *row = _mm256_broadcastsi128_si256(_mm_loadu_si128(
+            decode_table[i * 16..].as_ptr() as *const __m128i
+        ));
+    }
+    let gather = _mm256_broadcastsi128_si256(_mm_loadu_si128(GATHER.as_ptr() as *const __m128i));
+
+    let mut input_index = 0;
+    let mut output_index = 0;
+
+    // the second lane's output is stored right after the first lane's, overwriting its excess
+    while input.len() - input_index >= 2 * DECODE_INPUT_STEP_LEN
+        && output.len() - output_index >= DECODE_OUTPUT_STEP_LEN + DECODE_OUTPUT_STORE_LEN
+    {
+        let symbols = _mm256_loadu_si256(input[input_index..].as_ptr() as *const __m256i);
+
+        let high_nibbles = _mm256_and_si256(_mm256_srli_epi16(symbols, 4), _mm256_set1_epi8(0x0F));
+        let mut quintets = _mm256_setzero_si256();
+        for (i, row) in rows.iter().enumerate() {
+            let in_row = _mm256_cmpeq_epi8(high_nibbles, _mm256_set1_epi8(i as i8));
+            quintets = _mm256_or_si256(
+                quintets,
+                _mm256_and_si256(in_row, _mm256_shuffle_epi8(*row, symbols)),
+            );
+        }
+
+        if _mm256_movemask_epi8(_mm256_or_si256(quintets, symbols)) != 0 {
+            break;
+        }
+
+        let pairs = _mm256_maddubs_epi16(quintets, _mm256_set1_epi16(0x0120));
+        let fours = _mm256_madd_epi16(pairs, _mm256_set1_epi32(0x0001_0400));
+        let joined = _mm256_or_si256(
+            _mm256_srli_epi64(fours, 32),
+            _mm256_and_si256(
+                _mm256_slli_epi64(fours, 20),
+                _mm256_set1_epi64x(JOINED_MASK),
+            ),
+        );
+        let bytes = _mm256_shuffle_epi8(joined, gather);
+
+        _mm_storeu_si128(
+            output[output_index..].as_mut_ptr() as *mut __m128i,
+            _mm256_castsi256_si128(bytes),
+        );
+        _mm_storeu_si128(
+            output[output_index + DECODE_OUTPUT_STEP_LEN..].as_mut_ptr() as *mut __m128i,
+            _mm256_extracti128_si256(bytes, 1),
+        );
+
+        input_index += 2 * DECODE_INPUT_STEP_LEN;
+        output_index += 2 * DECODE_OUTPUT_STEP_LEN;
+    }
+
+    decode_ssse3(decode_table, output, input, input_index, output_index)
+}
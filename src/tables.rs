@@ -1,4 +1,10 @@
 pub(crate) const INVALID_BYTE: u8 = 0xFF;
+#[cfg(feature = "pair-decode")]
+pub(crate) const INVALID_PAIR: u16 = 0xFFFF;
+
+#[cfg(feature = "pair-decode")]
+use crate::PairDecodeTable;
+use crate::{Alphabet, PairEncodeTable};
 
 pub(crate) const ENCODE_STD: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
 pub(crate) const DECODE_STD: &[u8; 256] = &[
@@ -60,6 +66,8 @@ pub(crate) const DECODE_CROCKFORD: &[u8; 256] = &[
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
 ];
 
+pub(crate) const CHECK_SYMBOLS: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
 pub(crate) const ENCODE_ZBASE32: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
 pub(crate) const DECODE_ZBASE32: &[u8; 256] = &[
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
@@ -79,3 +87,19 @@ pub(crate) const DECODE_ZBASE32: &[u8; 256] = &[
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
 ];
+
+pub(crate) const PAIR_ENCODE_STD: &PairEncodeTable = &PairEncodeTable::new(&Alphabet::STANDARD);
+pub(crate) const PAIR_ENCODE_HEX: &PairEncodeTable = &PairEncodeTable::new(&Alphabet::EXTENDED_HEX);
+pub(crate) const PAIR_ENCODE_CROCKFORD: &PairEncodeTable =
+    &PairEncodeTable::new(&Alphabet::CROCKFORD);
+pub(crate) const PAIR_ENCODE_ZBASE32: &PairEncodeTable = &PairEncodeTable::new(&Alphabet::ZBASE32);
+
+#[cfg(feature = "pair-decode")]
+pub(crate) const PAIR_DECODE_STD: &PairDecodeTable = &PairDecodeTable::new(&Alphabet::STANDARD);
+#[cfg(feature = "pair-decode")]
+pub(crate) const PAIR_DECODE_HEX: &PairDecodeTable = &PairDecodeTable::new(&Alphabet::EXTENDED_HEX);
+#[cfg(feature = "pair-decode")]
+pub(crate) const PAIR_DECODE_CROCKFORD: &PairDecodeTable =
+    &PairDecodeTable::new(&Alphabet::CROCKFORD);
+#[cfg(feature = "pair-decode")]
+pub(crate) const PAIR_DECODE_ZBASE32: &PairDecodeTable = &PairDecodeTable::new(&Alphabet::ZBASE32);
@@ -1,7 +1,13 @@
+use std::io::{self, Read, Write};
+
 use criterion::black_box;
 use proptest::prelude::*;
 
-use crate::{CROCKFORD, EXTENDED_HEX, STANDARD, ZBASE32};
+use crate::{
+    Alphabet, Case, Encoding, LineEnding, PaddingMode, CROCKFORD, EXTENDED_HEX, STANDARD, ZBASE32,
+};
+
+const GEOHASH: Encoding = Encoding::new(Alphabet::GEOHASH, None);
 
 proptest! {
     #[test]
@@ -20,6 +26,199 @@ proptest! {
         let _ = black_box(ZBASE32.decode(&s));
     }
 
+    #[test]
+    fn custom_alphabet_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        assert_eq!(GEOHASH.decode(GEOHASH.encode(&data)).unwrap(), data);
+        assert_eq!(GEOHASH.with_padding(Some(b'=')).decode(GEOHASH.with_padding(Some(b'=')).encode(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn check_symbol_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        assert_eq!(CROCKFORD.decode_with_check(CROCKFORD.encode_with_check(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn decode_ignoring_hyphens(data in proptest::collection::vec(any::<u8>(), 0..256), hyphens in proptest::collection::vec(any::<prop::sample::Index>(), 0..32)) {
+        let mut encoded = CROCKFORD.encode(&data).into_bytes();
+        for index in hyphens {
+            encoded.insert(index.index(encoded.len() + 1), b'-');
+        }
+
+        assert_eq!(CROCKFORD.with_ignored(b"-").decode(encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn encoder_writer_matches_encode(data in proptest::collection::vec(any::<u8>(), 0..4096), splits in proptest::collection::vec(1..64usize, 1..32)) {
+        for encoding in [STANDARD, CROCKFORD.with_padding(Some(b'='))] {
+            let mut writer = crate::write::EncoderWriter::new(Vec::new(), encoding);
+            let mut remaining = &data[..];
+            for split in splits.iter().cycle() {
+                if remaining.is_empty() {
+                    break;
+                }
+                let (piece, rest) = remaining.split_at((*split).min(remaining.len()));
+                writer.write_all(piece).unwrap();
+                remaining = rest;
+            }
+
+            assert_eq!(writer.finish().unwrap(), encoding.encode(&data).into_bytes());
+        }
+    }
+
+    #[test]
+    fn decoder_reader_matches_decode(data in proptest::collection::vec(any::<u8>(), 0..4096), splits in proptest::collection::vec(1..64usize, 1..32)) {
+        for encoding in [STANDARD, CROCKFORD.with_ignored(b"-")] {
+            let encoded = encoding.encode(&data);
+            let mut reader = crate::read::DecoderReader::new(PieceReader(encoded.as_bytes(), splits.iter().cycle()), encoding);
+            let mut decoded = Vec::new();
+            let _ = reader.read_to_end(&mut decoded).unwrap();
+
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn display_matches_encode(data in proptest::collection::vec(any::<u8>(), 0..2048)) {
+        for encoding in [STANDARD, EXTENDED_HEX, CROCKFORD, ZBASE32] {
+            assert_eq!(encoding.display(&data).to_string(), encoding.encode(&data));
+        }
+    }
+
+    #[test]
+    fn append_to_existing_buffers(prefix in "[A-Z2-7]*", data in proptest::collection::vec(any::<u8>(), 0..1024)) {
+        let mut encoded = prefix.clone();
+        STANDARD.encode_to_string(&data, &mut encoded);
+        assert_eq!(encoded, prefix.clone() + &STANDARD.encode(&data));
+
+        let mut decoded = prefix.clone().into_bytes();
+        assert_eq!(STANDARD.decode_to_vec(STANDARD.encode(&data), &mut decoded).unwrap(), data.len());
+        assert_eq!(decoded, [prefix.as_bytes(), &data].concat());
+    }
+
+    #[test]
+    fn canonical_decoding_accepts_encoded(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        for encoding in [STANDARD, EXTENDED_HEX, CROCKFORD, ZBASE32] {
+            let encoding = encoding.with_canonical_decoding(true);
+            assert_eq!(encoding.decode(encoding.encode(&data)).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn padding_modes_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        let padded = STANDARD.encode(&data);
+        let unpadded = STANDARD.with_padding(None).encode(&data);
+
+        for mode in [PaddingMode::Required, PaddingMode::Optional] {
+            assert_eq!(STANDARD.with_padding_mode(mode).decode(&padded).unwrap(), data);
+        }
+        for mode in [PaddingMode::Optional, PaddingMode::Forbidden] {
+            assert_eq!(STANDARD.with_padding_mode(mode).decode(&unpadded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn decode_into_exact_buffer(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        const HYPHENATED: Encoding = CROCKFORD.with_ignored(b"-");
+
+        for encoding in [STANDARD, STANDARD.with_padding(None), HYPHENATED] {
+            let mut encoded = encoding.encode(&data);
+            if encoding.ignore.contains(b'-') {
+                encoded.insert(encoded.len() / 2, '-');
+            }
+
+            let mut buf = vec![0; encoding.decoded_len(encoded.as_bytes()).unwrap()];
+            assert_eq!(buf.len(), data.len());
+            assert_eq!(encoding.decode_to_slice(&mut buf, encoded.as_bytes()), Ok(data.len()));
+            assert_eq!(buf, data);
+        }
+    }
+
+    #[test]
+    fn try_to_slice_doesnt_panic(
+        data in proptest::collection::vec(any::<u8>(), 0..128),
+        s in "[A-Z2-7=!-]{0,128}",
+        len in 0usize..256,
+    ) {
+        const HYPHENATED: Encoding = STANDARD.with_ignored(b"-");
+        let mut buf = vec![0; len];
+
+        match STANDARD.try_encode_to_slice(&mut buf, &data) {
+            Ok(written) => assert_eq!(&buf[..written], STANDARD.encode(&data).as_bytes()),
+            Err(_) => assert!(len < STANDARD.encoded_size(data.len()).unwrap()),
+        }
+        for encoding in [STANDARD, HYPHENATED] {
+            if let Ok(written) = encoding.try_decode_to_slice(&mut buf, s.as_bytes()) {
+                assert_eq!(&buf[..written], &encoding.decode(&s).unwrap()[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn line_wrap_roundtrip(
+        data in proptest::collection::vec(any::<u8>(), 0..1024),
+        width in 1..100usize,
+        crlf in any::<bool>(),
+        split in 1..64usize,
+    ) {
+        let ending = if crlf { LineEnding::CrLf } else { LineEnding::Lf };
+        let encoding = STANDARD.with_line_wrap(width, ending);
+        let separator = if crlf { "\r\n" } else { "\n" };
+        let expected = STANDARD
+            .encode(&data)
+            .as_bytes()
+            .chunks(width)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        assert_eq!(encoding.encode(&data), expected);
+        assert_eq!(encoding.encoded_size(data.len()), Some(expected.len()));
+        assert_eq!(encoding.display(&data).to_string(), expected);
+
+        let mut writer = crate::write::EncoderWriter::new(Vec::new(), encoding);
+        for piece in data.chunks(split) {
+            writer.write_all(piece).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), expected.as_bytes());
+
+        assert_eq!(encoding.decode(&expected).unwrap(), data);
+    }
+
+    #[test]
+    fn grouping_roundtrip(
+        data in proptest::collection::vec(any::<u8>(), 0..1024),
+        size in 1..20usize,
+        width in proptest::option::of(1..100usize),
+        split in 1..64usize,
+    ) {
+        let mut encoding = CROCKFORD.with_grouping(size, b'-');
+        if let Some(width) = width {
+            encoding = encoding.with_line_wrap(width, LineEnding::Lf);
+        }
+
+        let mut expected = String::new();
+        for (i, c) in CROCKFORD.encode(&data).chars().enumerate() {
+            if i > 0 && width.map_or(false, |width| i % width == 0) {
+                expected.push('\n');
+            } else if i > 0 && i % size == 0 {
+                expected.push('-');
+            }
+            expected.push(c);
+        }
+
+        assert_eq!(encoding.encode(&data), expected);
+        assert_eq!(encoding.encoded_size(data.len()), Some(expected.len()));
+        assert_eq!(encoding.display(&data).to_string(), expected);
+
+        let mut writer = crate::write::EncoderWriter::new(Vec::new(), encoding);
+        for piece in data.chunks(split) {
+            writer.write_all(piece).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), expected.as_bytes());
+
+        assert_eq!(encoding.decode(&expected).unwrap(), data);
+    }
+
     #[test]
     fn decode_standard_is_ok(s in "(?:[A-Z2-7]{8})*(?:[A-Z2-7]{2}={6}|[A-Z2-7]{4}={4}|[A-Z2-7]{5}={3}|[A-Z2-7]{7}=)?") {
         assert!(STANDARD.decode(s).is_ok());
@@ -39,6 +238,39 @@ proptest! {
     fn decode_zbase32_is_ok(s in "(?:[ybndrfg8ejkmcpqxot1uwisza345h769]{8})*(?:[ybndrfg8ejkmcpqxot1uwisza345h769]{2}|[ybndrfg8ejkmcpqxot1uwisza345h769]{4}|[ybndrfg8ejkmcpqxot1uwisza345h769]{5}|[ybndrfg8ejkmcpqxot1uwisza345h769]{7})?") {
         assert!(ZBASE32.decode(s).is_ok());
     }
+
+    #[test]
+    fn geohash_contains_coordinates(lat in -90.0..=90.0f64, lon in -180.0..=180.0f64, precision in 1..16usize) {
+        let hash = crate::geohash::encode(lat, lon, precision).unwrap();
+        let bbox = crate::geohash::bounding_box(&hash).unwrap();
+
+        assert!(bbox.min_lat <= lat && lat <= bbox.max_lat);
+        assert!(bbox.min_lon <= lon && lon <= bbox.max_lon);
+        for (direction, neighbor) in crate::geohash::Direction::ALL.iter().zip(crate::geohash::neighbors(&hash).unwrap()) {
+            if let Some(neighbor) = neighbor {
+                assert_eq!(crate::geohash::neighbor(&neighbor, opposite(*direction)).unwrap(), Some(hash.clone()));
+            }
+        }
+    }
+}
+
+fn opposite(direction: crate::geohash::Direction) -> crate::geohash::Direction {
+    let all = crate::geohash::Direction::ALL;
+    let index = all.iter().position(|d| *d == direction).unwrap();
+
+    all[(index + 4) % all.len()]
+}
+
+/// Returns data in pieces of varying size.
+struct PieceReader<'a, I>(&'a [u8], I);
+
+impl<'a, I: Iterator<Item = &'a usize>> Read for PieceReader<'a, I> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = (*self.1.next().unwrap()).min(buf.len()).min(self.0.len());
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
 }
 
 #[test]
@@ -140,3 +372,840 @@ fn custom_padding_decode() {
         .decode(original.replace('=', "+"))
         .is_ok());
 }
+
+#[test]
+fn display_pads_to_width() {
+    let display = STANDARD.display(b"f");
+
+    assert_eq!(format!("[{:>20}]", display), "[            MY======]");
+    assert_eq!(format!("[{:20}]", display), "[MY======            ]");
+    assert_eq!(format!("[{:*^13}]", display), "[**MY======***]");
+    assert_eq!(format!("[{:4}]", display), "[MY======]");
+    assert_eq!(format!("[{:>1$}]", display, 8), "[MY======]");
+
+    let wrapped = CROCKFORD
+        .with_grouping(2, b'-')
+        .with_line_wrap(4, LineEnding::CrLf);
+    let encoded = wrapped.encode(b"foobar");
+    assert_eq!(
+        format!("{:>30}", wrapped.display(b"foobar")),
+        format!("{:>30}", encoded)
+    );
+    assert_eq!(
+        format!("{:^30}", wrapped.display(b"foobar")),
+        format!("{:^30}", encoded)
+    );
+}
+
+#[test]
+fn generated_tables_match_presets() {
+    let standard = Alphabet::new(Alphabet::STANDARD.symbols());
+    assert_eq!(standard.decode_table(), Alphabet::STANDARD.decode_table());
+
+    let extended_hex = Alphabet::new(Alphabet::EXTENDED_HEX.symbols());
+    assert_eq!(
+        extended_hex.decode_table(),
+        Alphabet::EXTENDED_HEX.decode_table()
+    );
+
+    let zbase32 = Alphabet::new(Alphabet::ZBASE32.symbols());
+    assert_eq!(zbase32.decode_table(), Alphabet::ZBASE32.decode_table());
+}
+
+#[test]
+fn custom_alphabet_encode_decode() -> Result<(), crate::DecodeError> {
+    assert_eq!(GEOHASH.encode("foobar"), "dtrqysm1f8");
+    assert_eq!(GEOHASH.decode("dtrqysm1f8")?, b"foobar");
+    assert_eq!(GEOHASH.decode("DTRQYSM1F8")?, b"foobar");
+
+    let case_sensitive = Encoding::new(Alphabet::new(b"abcdefghijklmnopABCDEFGHIJKLMNOP"), None);
+    assert_eq!(case_sensitive.decode("ab")?, [0x00]);
+    assert_eq!(case_sensitive.decode("AB")?, [0x84]);
+
+    Ok(())
+}
+
+#[test]
+fn crockford_check_symbols() -> Result<(), crate::DecodeError> {
+    assert_eq!(CROCKFORD.encode_with_check(""), "0");
+    assert_eq!(CROCKFORD.encode_with_check("f"), "CR1");
+    assert_eq!(CROCKFORD.encode_with_check("foobar"), "CSQPYRK1E8R");
+    assert_eq!(CROCKFORD.encode_with_check([0x08]), "10*");
+    assert_eq!(CROCKFORD.encode_with_check([0x09]), "14U");
+
+    assert_eq!(CROCKFORD.decode_with_check("CSQPYRK1E8R")?, b"foobar");
+    assert_eq!(CROCKFORD.decode_with_check("csqpyrk1e8r")?, b"foobar");
+    assert_eq!(CROCKFORD.decode_with_check("14u")?, [0x09]);
+    // the check symbol covers the symbols themselves, including bits left over at the end
+    assert_eq!(CROCKFORD.decode_with_check("CSQPYRK1E9S")?, b"foobar");
+
+    assert!(matches!(
+        CROCKFORD.decode_with_check("CSQPYRK1E8S"),
+        Err(crate::DecodeError::ChecksumMismatch)
+    ));
+    assert!(matches!(
+        CROCKFORD.decode_with_check("CSQPYRK1E8!"),
+        Err(crate::DecodeError::InvalidByte(10, b'!'))
+    ));
+    assert!(matches!(
+        CROCKFORD.decode_with_check(""),
+        Err(crate::DecodeError::InvalidInputLength)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "check symbols require the Crockford alphabet")]
+fn check_symbols_for_other_alphabet() {
+    let _ = STANDARD.encode_with_check([0; 101]);
+}
+
+#[test]
+#[should_panic(expected = "check symbols must not be the padding byte or ignored")]
+fn check_symbols_conflicting_with_padding() {
+    let _ = CROCKFORD.with_padding(Some(b'=')).decode_with_check("CR1");
+}
+
+#[test]
+fn crockford_hyphens() -> Result<(), crate::DecodeError> {
+    const HYPHENATED: crate::Encoding = CROCKFORD.with_ignored(b"-");
+
+    assert_eq!(HYPHENATED.decode("CSQP-YRK1-E8")?, b"foobar");
+    assert_eq!(HYPHENATED.decode("-CSQPYRK1E8-")?, b"foobar");
+    assert_eq!(HYPHENATED.decode("C-S-Q-P-Y-R-K-1-E-8")?, b"foobar");
+    assert_eq!(HYPHENATED.decode("---")?, b"");
+    assert_eq!(HYPHENATED.decode_with_check("CSQP-YRK1-E8R")?, b"foobar");
+    assert_eq!(HYPHENATED.decode_with_check("CSQP-YRK1-E8-R-")?, b"foobar");
+    assert!(matches!(
+        HYPHENATED.decode_with_check("--"),
+        Err(crate::DecodeError::InvalidInputLength)
+    ));
+
+    let wrapped = CROCKFORD.with_line_wrap(4, crate::LineEnding::Lf);
+    assert_eq!(wrapped.decode_with_check("CSQP\nYRK1\nE8R\n")?, b"foobar");
+
+    assert!(matches!(
+        CROCKFORD.decode("CSQP-YRK1-E8"),
+        Err(crate::DecodeError::InvalidByte(4, b'-'))
+    ));
+    assert!(matches!(
+        HYPHENATED.decode("CSQP-YRK1-E8-C"),
+        Err(crate::DecodeError::InvalidInputLength)
+    ));
+
+    let long = "CSQPYRK1-CSQPYRK1-CSQPYRK1-CSQPYRK1-CSQPYRK1-CSQPYRK1-CSQ!YRK1-CSQPYRK1";
+    assert!(matches!(
+        HYPHENATED.decode(long),
+        Err(crate::DecodeError::InvalidByte(57, b'!'))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn encoder_writer_finishes_on_drop() {
+    let mut output = Vec::new();
+    {
+        let mut writer = crate::write::EncoderWriter::new(&mut output, STANDARD);
+        writer.write_all(b"foob").unwrap();
+    }
+
+    assert_eq!(output, b"MZXW6YQ=");
+}
+
+#[test]
+fn decoder_reader_errors() {
+    fn decode(input: &str, encoding: crate::Encoding) -> io::Result<Vec<u8>> {
+        let pieces = [3, 1, 7];
+        let reader = PieceReader(input.as_bytes(), pieces.iter().cycle());
+        let mut decoded = Vec::new();
+        let _ = crate::read::DecoderReader::new(reader, encoding).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+
+    fn decode_error(input: &str, encoding: crate::Encoding) -> crate::DecodeError {
+        let error = decode(input, encoding).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        *error.into_inner().unwrap().downcast().unwrap()
+    }
+
+    assert_eq!(decode("MZXW6YTBOI======", STANDARD).unwrap(), b"foobar");
+    assert_eq!(
+        decode("CSQP-YRK1-E8", CROCKFORD.with_ignored(b"-")).unwrap(),
+        b"foobar"
+    );
+
+    assert!(matches!(
+        decode_error("MZXW6YTBOI!=====", STANDARD),
+        crate::DecodeError::InvalidByte(10, b'!')
+    ));
+    assert!(matches!(
+        decode_error("MZXW6YTBMZXW6YTBM!XW6YTB", STANDARD),
+        crate::DecodeError::InvalidByte(17, b'!')
+    ));
+    assert!(matches!(
+        decode_error("MY======MZXQ====", STANDARD),
+        crate::DecodeError::InvalidByte(8, b'M')
+    ));
+    assert!(matches!(
+        decode_error(
+            "MZXW6YTBOI=",
+            STANDARD.with_padding_mode(PaddingMode::Required)
+        ),
+        crate::DecodeError::InvalidPadding(10)
+    ));
+    assert!(matches!(
+        decode_error("CSQP-YRK1-E!", CROCKFORD.with_ignored(b"-")),
+        crate::DecodeError::InvalidByte(11, b'!')
+    ));
+}
+
+#[test]
+fn decode_to_vec_rolls_back_on_error() {
+    let mut decoded = b"foo".to_vec();
+
+    assert!(STANDARD
+        .decode_to_vec("MJQXEMJQXEMJQXE!", &mut decoded)
+        .is_err());
+    assert_eq!(decoded, b"foo");
+}
+
+#[test]
+fn canonical_decoding_rejects_trailing_bits() -> Result<(), crate::DecodeError> {
+    let strict = STANDARD.with_canonical_decoding(true);
+
+    assert_eq!(STANDARD.decode("MZ======")?, b"f");
+    assert_eq!(strict.decode("MY======")?, b"f");
+    assert!(matches!(
+        strict.decode("MZ======"),
+        Err(crate::DecodeError::InvalidLastSymbol(1, b'Z'))
+    ));
+    assert!(matches!(
+        strict.decode("MZXW6YR="),
+        Err(crate::DecodeError::InvalidLastSymbol(6, b'R'))
+    ));
+    assert!(matches!(
+        strict.with_padding(None).decode("MZXW6YTBOJ"),
+        Err(crate::DecodeError::InvalidLastSymbol(9, b'J'))
+    ));
+    assert!(matches!(
+        CROCKFORD.with_canonical_decoding(true).decode("CSQPYRK1E9"),
+        Err(crate::DecodeError::InvalidLastSymbol(9, b'9'))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn padding_modes() {
+    use crate::DecodeError::*;
+
+    let required = STANDARD.with_padding_mode(PaddingMode::Required);
+    let forbidden = STANDARD.with_padding_mode(PaddingMode::Forbidden);
+
+    assert!(matches!(required.decode("MZXW6"), Err(MissingPadding)));
+    assert!(matches!(required.decode("MZXW6YTBOI"), Err(MissingPadding)));
+    assert!(required.decode("MZXW6YTB").is_ok());
+    assert!(matches!(
+        forbidden.decode("MZXW6==="),
+        Err(UnexpectedPadding(5))
+    ));
+    assert!(matches!(
+        forbidden.decode("MZXW6YTBOI======"),
+        Err(UnexpectedPadding(10))
+    ));
+    assert!(matches!(forbidden.decode("MY="), Err(UnexpectedPadding(2))));
+
+    for encoding in [STANDARD, required] {
+        assert!(matches!(
+            encoding.decode("A======="),
+            Err(InvalidPadding(1))
+        ));
+        assert!(matches!(
+            encoding.decode("AAA====="),
+            Err(InvalidPadding(3))
+        ));
+        assert!(matches!(
+            encoding.decode("AAAAAA=="),
+            Err(InvalidPadding(6))
+        ));
+        assert!(matches!(
+            encoding.decode("========"),
+            Err(InvalidPadding(0))
+        ));
+        // padding is to blame no matter how long the input is
+        assert!(matches!(
+            encoding.decode("MY======="),
+            Err(InvalidPadding(2))
+        ));
+        assert!(matches!(
+            encoding.decode("MZXW6YTB========"),
+            Err(InvalidPadding(8))
+        ));
+        assert!(matches!(
+            encoding.decode("MY=A===="),
+            Err(InvalidByte(3, b'A'))
+        ));
+        assert!(matches!(
+            encoding.decode("MY======MY======"),
+            Err(InvalidByte(2, b'='))
+        ));
+    }
+
+    assert!(matches!(required.decode("MY=="), Err(InvalidPadding(2))));
+    assert!(matches!(required.decode("MY="), Err(InvalidPadding(2))));
+    assert!(matches!(required.decode("MY====="), Err(InvalidPadding(2))));
+    assert!(matches!(
+        required.with_ignored(b"-").decode("MY=-"),
+        Err(InvalidPadding(2))
+    ));
+
+    // optional padding may stop short of the end of the chunk
+    for &input in &["MY", "MY=", "MY==", "MY=====", "MY======"] {
+        assert_eq!(STANDARD.decode(input).unwrap(), b"f");
+        assert_eq!(STANDARD.decoded_len(input.as_bytes()), Ok(1));
+    }
+    assert_eq!(STANDARD.decode("MZXW6YTBMY==").unwrap(), b"foobaf");
+    assert_eq!(STANDARD.with_ignored(b"-").decode("MY=-").unwrap(), b"f");
+
+    // padding in the middle of the input is as unexpected as at its end
+    let padded = "MY======".to_owned() + &"MZXW6YTB".repeat(5);
+    for input in [&padded[..16], &padded] {
+        assert!(matches!(forbidden.decode(input), Err(UnexpectedPadding(2))));
+        assert!(matches!(
+            forbidden.with_ignored(b"-").decode(input),
+            Err(UnexpectedPadding(2))
+        ));
+    }
+}
+
+#[test]
+fn decode_errors_report_offending_byte() {
+    use crate::DecodeError::InvalidByte;
+
+    let mut input = "MZXW6YTB".repeat(8).into_bytes();
+    for &i in &[0, 9, 30, 47, 63] {
+        let original = input[i];
+        input[i] = b'!';
+        assert_eq!(STANDARD.decode(&input), Err(InvalidByte(i, b'!')));
+        input[i] = original;
+    }
+}
+
+#[test]
+fn configurable_case() -> Result<(), crate::DecodeError> {
+    const LOWER: Encoding = STANDARD.with_case(Case::Lower);
+    const LOWER_STRICT: Encoding = LOWER.with_case_sensitive_decoding(true);
+    const UPPER_STRICT: Encoding = STANDARD.with_case_sensitive_decoding(true);
+
+    assert_eq!(LOWER.encode("foobar"), "mzxw6ytboi======");
+    assert_eq!(
+        EXTENDED_HEX.with_case(Case::Lower).encode("foobar"),
+        "cpnmuoj1e8======"
+    );
+    assert_eq!(
+        ZBASE32.with_case(Case::Upper).encode("foobar"),
+        "C3ZS6AUBQE"
+    );
+    assert_eq!(
+        LOWER.with_case(Case::Upper).encode("foobar"),
+        STANDARD.encode("foobar")
+    );
+
+    assert_eq!(LOWER.decode("MZXW6YTBOI======")?, b"foobar");
+    assert_eq!(LOWER.decode("mzxw6ytboi======")?, b"foobar");
+    assert_eq!(LOWER_STRICT.decode("mzxw6ytboi======")?, b"foobar");
+    assert_eq!(UPPER_STRICT.decode("MZXW6YTBOI======")?, b"foobar");
+    assert_eq!(
+        LOWER_STRICT.decode("mzxw6Ytboi======"),
+        Err(crate::DecodeError::InvalidByte(5, b'Y'))
+    );
+    assert_eq!(
+        UPPER_STRICT.decode("MZXW6yTBOI======"),
+        Err(crate::DecodeError::InvalidByte(5, b'y'))
+    );
+
+    // switching case and sensitivity works in either order
+    let strict_first = STANDARD
+        .with_case_sensitive_decoding(true)
+        .with_case(Case::Lower);
+    assert_eq!(
+        strict_first.alphabet().decode_table()[..],
+        LOWER_STRICT.alphabet().decode_table()[..]
+    );
+    assert_eq!(
+        LOWER_STRICT
+            .with_case_sensitive_decoding(false)
+            .alphabet()
+            .decode_table()[..],
+        STANDARD.alphabet().decode_table()[..]
+    );
+
+    // aliases follow the case of the alphabet
+    let crockford = CROCKFORD.with_case_sensitive_decoding(true);
+    assert_eq!(crockford.decode("CSQPYRKIE8")?, b"foobar");
+    assert!(crockford.decode("CSQPYRKiE8").is_err());
+    let crockford = crockford.with_case(Case::Lower);
+    assert_eq!(crockford.decode("csqpyrkie8")?, b"foobar");
+    assert!(crockford.decode("csqpyrkIe8").is_err());
+    assert_eq!(crockford.encode_with_check([0x09]), "14u");
+
+    // so does the check symbol that isn't part of the alphabet
+    assert_eq!(crockford.decode_with_check("14u")?, [0x09]);
+    assert_eq!(
+        crockford.decode_with_check("14U"),
+        Err(crate::DecodeError::InvalidByte(2, b'U'))
+    );
+    let crockford = crockford.with_case(Case::Upper);
+    assert_eq!(crockford.decode_with_check("14U")?, [0x09]);
+    assert_eq!(
+        crockford.decode_with_check("14u"),
+        Err(crate::DecodeError::InvalidByte(2, b'u'))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn whitespace_tolerant_decoding() -> Result<(), crate::DecodeError> {
+    const WRAPPED: Encoding = STANDARD.with_ignored_whitespace();
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    let encoded = STANDARD.encode(&data);
+    let wrapped = encoded
+        .as_bytes()
+        .chunks(76)
+        .map(|line| format!("    {}\r\n", std::str::from_utf8(line).unwrap()))
+        .collect::<String>();
+
+    assert_eq!(WRAPPED.decode(&wrapped)?, data);
+    assert_eq!(WRAPPED.decode(" \tMZXW6YTB\x0COI======\n")?, b"foobar");
+    assert_eq!(
+        STANDARD.decode("MZXW6YTB\nOI====="),
+        Err(crate::DecodeError::InvalidByte(8, b'\n'))
+    );
+
+    // errors are reported at their position in the original input
+    let mut invalid = wrapped.into_bytes();
+    invalid[200] = b'!';
+    assert_eq!(
+        WRAPPED.decode(&invalid),
+        Err(crate::DecodeError::InvalidByte(200, b'!'))
+    );
+
+    let mut decoded = Vec::new();
+    let _ = crate::read::DecoderReader::new(&b"MZXW6\nYTBOI===\n===\n"[..], WRAPPED)
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, b"foobar");
+
+    Ok(())
+}
+
+#[test]
+fn line_wrap() {
+    const WRAPPED: Encoding = STANDARD.with_line_wrap(4, LineEnding::CrLf);
+
+    assert_eq!(WRAPPED.encode(""), "");
+    assert_eq!(WRAPPED.encode("f"), "MY==\r\n====");
+    assert_eq!(WRAPPED.encode("fo"), "MZXQ\r\n====");
+    assert_eq!(
+        STANDARD
+            .with_line_wrap(8, LineEnding::Lf)
+            .encode("foobarfoo"),
+        "MZXW6YTB\nOJTG63Y="
+    );
+    assert_eq!(WRAPPED.encoded_size(1), Some(10));
+
+    let mut buf = [0; 10];
+    assert_eq!(WRAPPED.encode_to_slice(&mut buf, b"f"), 10);
+    assert_eq!(&buf, b"MY==\r\n====");
+    assert!(WRAPPED.try_encode_to_slice(&mut buf[..8], b"f").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_fields() {
+    use serde::{Deserialize, Serialize};
+
+    mod lower {
+        use crate::{Case, Encoding};
+
+        const LOWER: Encoding = crate::STANDARD.with_case(Case::Lower).with_padding(None);
+
+        pub fn serialize<S: serde::Serializer>(data: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            crate::serde::serialize_with(&LOWER, data, s)
+        }
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            crate::serde::deserialize_with(&LOWER, d)
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fields {
+        #[serde(with = "crate::serde::standard")]
+        standard: Vec<u8>,
+        #[serde(with = "crate::serde::extended_hex")]
+        extended_hex: [u8; 3],
+        #[serde(with = "crate::serde::crockford")]
+        crockford: Vec<u8>,
+        #[serde(with = "crate::serde::zbase32")]
+        zbase32: Vec<u8>,
+        #[serde(with = "lower")]
+        lower: Vec<u8>,
+    }
+
+    let fields = Fields {
+        standard: b"foobar".to_vec(),
+        extended_hex: *b"foo",
+        crockford: b"foobar".to_vec(),
+        zbase32: vec![0xF0, 0xBF, 0xC7],
+        lower: b"foobar".to_vec(),
+    };
+    let json = r#"{"standard":"MZXW6YTBOI======","extended_hex":"CPNMU===","crockford":"CSQPYRK1E8","zbase32":"6n9hq","lower":"mzxw6ytboi"}"#;
+
+    assert_eq!(serde_json::to_string(&fields).unwrap(), json);
+    assert_eq!(serde_json::from_str::<Fields>(json).unwrap(), fields);
+
+    let invalid = json.replace("CPNMU===", "CPNM!===");
+    let error = serde_json::from_str::<Fields>(&invalid).unwrap_err();
+    assert!(error.to_string().contains("invalid input byte at index 4"));
+
+    let wrong_length = json.replace("CPNMU===", "CPNMUOJ1");
+    let error = serde_json::from_str::<Fields>(&wrong_length).unwrap_err();
+    assert!(error.to_string().contains("invalid length 5"));
+}
+
+#[test]
+fn encode_matches_reference() {
+    use rand::{Rng, SeedableRng};
+
+    fn reference(encoding: &Encoding, data: &[u8]) -> String {
+        let symbols = encoding.alphabet().symbols();
+        let bits = data.len() * 8;
+        (0..(bits + 4) / 5)
+            .map(|i| {
+                let value = (0..5).fold(0, |acc, bit| {
+                    let n = i * 5 + bit;
+                    let set = n < bits && data[n / 8] & (0x80 >> (n % 8)) != 0;
+                    acc << 1 | set as usize
+                });
+                symbols[value] as char
+            })
+            .collect()
+    }
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+    let lower = STANDARD.with_case(Case::Lower);
+
+    for len in 0..300 {
+        let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+
+        for encoding in [STANDARD, EXTENDED_HEX, CROCKFORD, ZBASE32, lower] {
+            let unpadded = encoding.with_padding(None);
+            assert_eq!(unpadded.encode(&data), reference(&encoding, &data));
+        }
+    }
+}
+
+#[test]
+fn decode_reports_first_invalid_symbol() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+    let lower = STANDARD.with_case(Case::Lower);
+
+    for len in 0..300 {
+        let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+
+        for encoding in [STANDARD, EXTENDED_HEX, CROCKFORD, ZBASE32, lower] {
+            let encoded = encoding.encode(&data).into_bytes();
+            assert_eq!(encoding.decode(&encoded).unwrap(), data);
+
+            let symbols = encoded.iter().take_while(|b| **b != b'=').count();
+            if symbols < 2 {
+                continue;
+            }
+            for &invalid in &[b'!', b'\n', 0x80, 0xFF] {
+                let index = rng.gen_range(0..symbols);
+                let mut corrupted = encoded.clone();
+                corrupted[index] = invalid;
+                assert_eq!(
+                    encoding.decode(&corrupted),
+                    Err(crate::DecodeError::InvalidByte(index, invalid))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn pair_encode_table() {
+    use crate::{Alphabet, Encoding, PairEncodeTable};
+
+    const ALPHABET: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+    const PAIRS: &PairEncodeTable = &PairEncodeTable::new(&ALPHABET);
+    const GEOHASH: Encoding = Encoding::new(ALPHABET, None).with_pair_encode_table(PAIRS);
+
+    let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+    let expected = Encoding::new(ALPHABET, None).encode(&data);
+    assert_eq!(GEOHASH.encode(&data), expected);
+
+    // the table no longer matches once letters are encoded in uppercase
+    assert_eq!(
+        GEOHASH.with_case(Case::Upper).encode(&data),
+        expected.to_uppercase()
+    );
+
+    // tables are kept out of the encoding so that it stays cheap to copy
+    assert!(core::mem::size_of::<Encoding>() < 512);
+}
+
+#[test]
+#[should_panic(expected = "pair encode table must be built for the alphabet of the encoding")]
+fn pair_encode_table_for_other_alphabet() {
+    let _ = STANDARD.with_pair_encode_table(crate::tables::PAIR_ENCODE_HEX);
+}
+
+#[cfg(feature = "pair-decode")]
+#[test]
+fn pair_decode_table() {
+    use crate::{Alphabet, Encoding, PairDecodeTable};
+
+    const ALPHABET: Alphabet = Alphabet::new(b"0123456789bcdefghjkmnpqrstuvwxyz");
+    const PAIRS: &PairDecodeTable = &PairDecodeTable::new(&ALPHABET);
+    const GEOHASH: Encoding = Encoding::new(ALPHABET, None).with_pair_decode_table(PAIRS);
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    for encoding in [GEOHASH, Encoding::new(ALPHABET, None)] {
+        let encoded = encoding.encode(&data);
+        assert_eq!(encoding.decode(&encoded).unwrap(), data);
+        assert_eq!(encoding.decode(encoded.to_uppercase()).unwrap(), data);
+
+        let mut corrupted = encoded.into_bytes();
+        corrupted[100] = b'a';
+        assert_eq!(
+            encoding.decode(&corrupted),
+            Err(crate::DecodeError::InvalidByte(100, b'a'))
+        );
+    }
+
+    // the table no longer matches once uppercase letters are rejected
+    let strict = GEOHASH.with_case_sensitive_decoding(true);
+    let encoded = strict.encode(&data);
+    assert_eq!(strict.decode(&encoded).unwrap(), data);
+    assert!(strict.decode(encoded.to_uppercase()).is_err());
+}
+
+#[cfg(feature = "pair-decode")]
+#[test]
+#[should_panic(expected = "pair decode table must be built for the alphabet of the encoding")]
+fn pair_decode_table_for_other_alphabet() {
+    let _ = STANDARD.with_pair_decode_table(crate::tables::PAIR_DECODE_HEX);
+}
+
+#[test]
+fn geohash() {
+    use crate::geohash::{self, BoundingBox, CoordinateError, Direction};
+
+    assert_eq!(
+        geohash::encode(57.64911, 10.40744, 11).unwrap(),
+        "u4pruydqqvj"
+    );
+    assert_eq!(geohash::encode(-90.0, -180.0, 6).unwrap(), "000000");
+    assert_eq!(geohash::encode(90.0, 180.0, 6).unwrap(), "zzzzzz");
+    assert_eq!(
+        geohash::encode(0.0, 0.0, 0),
+        Err(CoordinateError::InvalidPrecision(0))
+    );
+    assert_eq!(
+        geohash::encode_to_slice(&mut [], 0.0, 0.0),
+        Err(CoordinateError::InvalidPrecision(0))
+    );
+    let longest = geohash::encode(57.64911, 10.40744, geohash::MAX_PRECISION).unwrap();
+    assert!(longest.starts_with("u4pruydqqvj"));
+    for &(lat, lon) in &[
+        (57.64911, 10.40744),
+        (0.0, 179.99999),
+        (-89.99999, -179.99999),
+    ] {
+        let hash = geohash::encode(lat, lon, geohash::MAX_PRECISION).unwrap();
+        for &direction in &Direction::ALL {
+            let neighbor = geohash::neighbor(&hash, direction).unwrap();
+            assert_ne!(neighbor.as_ref(), Some(&hash), "{} {:?}", hash, direction);
+        }
+    }
+    assert_eq!(
+        geohash::encode(0.0, 0.0, usize::MAX),
+        Err(CoordinateError::InvalidPrecision(usize::MAX))
+    );
+    assert_eq!(
+        geohash::encode_to_slice(&mut [0; geohash::MAX_PRECISION + 1], 0.0, 0.0),
+        Err(CoordinateError::InvalidPrecision(
+            geohash::MAX_PRECISION + 1
+        ))
+    );
+    assert_eq!(
+        geohash::bounding_box(longest + "0"),
+        Err(crate::DecodeError::InvalidInputLength)
+    );
+    assert_eq!(
+        geohash::encode(90.5, 0.0, 5),
+        Err(CoordinateError::InvalidLatitude(90.5))
+    );
+    assert!(matches!(
+        geohash::encode(0.0, f64::NAN, 5),
+        Err(CoordinateError::InvalidLongitude(_))
+    ));
+
+    assert_eq!(
+        geohash::bounding_box("EZS42").unwrap(),
+        BoundingBox {
+            min_lat: 42.5830078125,
+            max_lat: 42.626953125,
+            min_lon: -5.625,
+            max_lon: -5.5810546875,
+        }
+    );
+    assert_eq!(
+        geohash::decode("ezs4a"),
+        Err(crate::DecodeError::InvalidByte(4, b'a'))
+    );
+    assert_eq!(
+        geohash::decode(""),
+        Err(crate::DecodeError::InvalidInputLength)
+    );
+
+    let neighbors = geohash::neighbors("gbsuv").unwrap();
+    let expected = [
+        "gbsvj", "gbsvn", "gbsuy", "gbsuw", "gbsut", "gbsus", "gbsuu", "gbsvh",
+    ];
+    for (neighbor, expected) in neighbors.iter().zip(&expected) {
+        assert_eq!(neighbor.as_deref(), Some(*expected));
+    }
+
+    assert_eq!(
+        geohash::neighbor("bp", Direction::West).unwrap().as_deref(),
+        Some("zz")
+    );
+    assert_eq!(geohash::neighbor("00", Direction::SouthEast).unwrap(), None);
+}
+
+#[test]
+fn bech32() {
+    use crate::bech32::{self, Error, Variant};
+
+    // test vectors from BIP-173 and BIP-350, some of which don't hold whole bytes
+    let valid = [
+        ("A12UEL5L", Variant::Bech32),
+        ("a12uel5l", Variant::Bech32),
+        ("an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs", Variant::Bech32),
+        ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Variant::Bech32),
+        ("11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j", Variant::Bech32),
+        ("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", Variant::Bech32),
+        ("?1ezyfcl", Variant::Bech32),
+        ("A1LQFN3A", Variant::Bech32m),
+        ("a1lqfn3a", Variant::Bech32m),
+        ("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Variant::Bech32m),
+        ("split1checkupstagehandshakeupstreamerranterredcaperredlc445v", Variant::Bech32m),
+        ("?1v759aa", Variant::Bech32m),
+    ];
+    for (s, variant) in valid {
+        let (hrp, values, v) = bech32::decode(s).unwrap();
+        assert_eq!(v, variant);
+        assert_eq!(
+            bech32::encode_values(&hrp, &values, v).unwrap(),
+            s.to_lowercase()
+        );
+    }
+
+    // segwit addresses from BIP-173 and BIP-350, which start with a witness version
+    // followed by a program of whole bytes
+    let addresses: [(&str, u8, &str, Variant); 6] = [
+        (
+            "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+            0,
+            "751e76e8199196d454941c45d1b3a323f1433bd6",
+            Variant::Bech32,
+        ),
+        (
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+            0,
+            "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            Variant::Bech32,
+        ),
+        (
+            "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+            1,
+            "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+            Variant::Bech32m,
+        ),
+        ("BC1SW50QGDZ25J", 16, "751e", Variant::Bech32m),
+        (
+            "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+            2,
+            "751e76e8199196d454941c45d1b3a323",
+            Variant::Bech32m,
+        ),
+        (
+            "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+            1,
+            "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            Variant::Bech32m,
+        ),
+    ];
+    for (s, version, program, variant) in addresses {
+        let (_, values, v) = bech32::decode(s).unwrap();
+        let program = (0..program.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&program[i..i + 2], 16).unwrap())
+            .collect::<Vec<u8>>();
+
+        assert_eq!((values[0], v), (version, variant), "{}", s);
+        assert_eq!(bech32::to_bytes(&values[1..]).unwrap(), program, "{}", s);
+    }
+
+    let (_, values, _) = bech32::decode("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+    assert_eq!(bech32::to_bytes(&values), Err(Error::InvalidPadding));
+    assert_eq!(bech32::to_bytes(&[0, 32]), Err(Error::InvalidValue(1, 32)));
+
+    let invalid = [
+        ("\x201nwldj5", Error::InvalidByte(0, 0x20)),
+        ("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx", Error::InvalidLength(91)),
+        ("pzry9x0s0muk", Error::MissingSeparator),
+        ("1pzry9x0s0muk", Error::EmptyHrp),
+        ("x1b4n0q5v", Error::InvalidByte(2, b'b')),
+        ("li1dgmt3", Error::InvalidLength(8)),
+        ("A1G7SGD8", Error::ChecksumMismatch(None)),
+        ("10a06t8", Error::EmptyHrp),
+        ("a1lqfN3a", Error::MixedCase),
+    ];
+    for (s, error) in invalid {
+        assert_eq!(bech32::decode(s), Err(error), "{}", s);
+    }
+
+    // a public key from NIP-19
+    let key = [
+        0x7e, 0x7e, 0x9c, 0x42, 0xa9, 0x1b, 0xfe, 0xf1, 0x9f, 0xa9, 0x29, 0xe5, 0xfd, 0xa1, 0xb7,
+        0x2e, 0x0e, 0xbc, 0x1a, 0x4c, 0x11, 0x41, 0x67, 0x3e, 0x27, 0x94, 0x23, 0x4d, 0x86, 0xad,
+        0xdf, 0x4e,
+    ];
+    let npub = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+    assert_eq!(bech32::encode("npub", &key, Variant::Bech32).unwrap(), npub);
+    let (hrp, values, variant) = bech32::decode(npub).unwrap();
+    assert_eq!((hrp.as_str(), variant), ("npub", Variant::Bech32));
+    assert_eq!(bech32::to_bytes(&values).unwrap(), key);
+
+    // every single symbol typo is located
+    for i in 5..npub.len() {
+        for &symbol in Alphabet::BECH32.symbols() {
+            let mut typo = npub.as_bytes().to_vec();
+            if typo[i] != symbol {
+                typo[i] = symbol;
+                assert_eq!(bech32::decode(&typo), Err(Error::ChecksumMismatch(Some(i))));
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Streaming encoding through [`std::io::Write`].
+use core::{cmp, fmt};
+use std::io::{self, Write};
+
+use crate::Encoding;
+
+const INPUT_CHUNK_LEN: usize = 5;
+
+// Must hold at least a single chunk, including separators
+const OUTPUT_BUF_LEN: usize = 1024;
+
+/// Encodes everything written to it and passes the result on to the wrapped writer.
+///
+/// Input that doesn't make up a whole chunk of five bytes is held back until more is written,
+/// and encoded along with any padding by [`finish`](EncoderWriter::finish). Dropping the
+/// writer also finishes it, but any errors are silently ignored.
+/// ```
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut writer = base32::write::EncoderWriter::new(Vec::new(), base32::STANDARD);
+///     writer.write_all(b"foo")?;
+///     writer.write_all(b"bar")?;
+///
+///     assert_eq!(writer.finish()?, b"MZXW6YTBOI======");
+///     Ok(())
+/// }
+/// ```
+pub struct EncoderWriter<W: Write> {
+    encoding: Encoding,
+    delegate: Option<W>,
+    chunk: [u8; INPUT_CHUNK_LEN],
+    chunk_len: usize,
+    output: [u8; OUTPUT_BUF_LEN],
+    output_len: usize,
+    // amount of symbols encoded so far, to keep track of where separators go
+    symbols: usize,
+    panicked: bool,
+}
+
+impl<W: Write> EncoderWriter<W> {
+    /// Creates a new writer that encodes according to the
+    /// given configuration before writing to the delegate.
+    pub fn new(delegate: W, encoding: Encoding) -> EncoderWriter<W> {
+        EncoderWriter {
+            encoding,
+            delegate: Some(delegate),
+            chunk: [0; INPUT_CHUNK_LEN],
+            chunk_len: 0,
+            output: [0; OUTPUT_BUF_LEN],
+            output_len: 0,
+            symbols: 0,
+            panicked: false,
+        }
+    }
+
+    /// Encodes any remaining input including padding, writes
+    /// everything to the delegate and returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_final()?;
+
+        Ok(self
+            .delegate
+            .take()
+            .expect("Writer must be present until finished"))
+    }
+
+    fn write_final(&mut self) -> io::Result<()> {
+        if self.chunk_len > 0 {
+            if OUTPUT_BUF_LEN - self.output_len < self.encoding.max_chunk_len() {
+                self.write_output()?;
+            }
+
+            let chunk = self.chunk;
+            self.encode_output(&chunk[..self.chunk_len]);
+            self.chunk_len = 0;
+        }
+
+        self.write_output()
+    }
+
+    /// Encodes the input into the output buffer, along with any separators in between.
+    fn encode_output(&mut self, input: &[u8]) {
+        let output = &mut self.output[self.output_len..];
+        let symbols = self.encoding.encode_symbols_to_slice(output, input);
+
+        self.output_len += self
+            .encoding
+            .insert_separators(output, self.symbols, symbols);
+        self.symbols += symbols;
+    }
+
+    /// Writes all encoded output to the delegate, keeping whatever
+    /// wasn't written around for the next attempt on errors.
+    fn write_output(&mut self) -> io::Result<()> {
+        let delegate = self
+            .delegate
+            .as_mut()
+            .expect("Writer must be present until finished");
+
+        let mut written = 0;
+        let mut result = Ok(());
+
+        while written < self.output_len {
+            self.panicked = true;
+            let r = delegate.write(&self.output[written..self.output_len]);
+            self.panicked = false;
+
+            match r {
+                Ok(0) => {
+                    result = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write encoded data",
+                    ));
+                    break;
+                }
+                Ok(n) => written += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.output.copy_within(written..self.output_len, 0);
+        self.output_len -= written;
+
+        result
+    }
+}
+
+impl<W: Write> Write for EncoderWriter<W> {
+    fn write(&mut self, input: &[u8]) -> io::Result<usize> {
+        assert!(
+            self.delegate.is_some(),
+            "Cannot write more after calling finish()"
+        );
+
+        if input.is_empty() {
+            return Ok(0);
+        }
+
+        let max_chunk_len = self.encoding.max_chunk_len();
+        if OUTPUT_BUF_LEN - self.output_len < max_chunk_len {
+            self.write_output()?;
+        }
+
+        let mut consumed = 0;
+
+        if self.chunk_len > 0 {
+            consumed = cmp::min(INPUT_CHUNK_LEN - self.chunk_len, input.len());
+            self.chunk[self.chunk_len..self.chunk_len + consumed]
+                .copy_from_slice(&input[..consumed]);
+            self.chunk_len += consumed;
+
+            if self.chunk_len < INPUT_CHUNK_LEN {
+                return Ok(consumed);
+            }
+
+            let chunk = self.chunk;
+            self.encode_output(&chunk);
+            self.chunk_len = 0;
+        }
+
+        let chunks = cmp::min(
+            (input.len() - consumed) / INPUT_CHUNK_LEN,
+            (OUTPUT_BUF_LEN - self.output_len) / max_chunk_len,
+        );
+        let end = consumed + chunks * INPUT_CHUNK_LEN;
+
+        self.encode_output(&input[consumed..end]);
+        consumed = end;
+
+        // only hold on to the remainder once every whole chunk has been encoded
+        if input.len() - consumed < INPUT_CHUNK_LEN {
+            self.chunk_len = input.len() - consumed;
+            self.chunk[..self.chunk_len].copy_from_slice(&input[consumed..]);
+            consumed = input.len();
+        }
+
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_output()?;
+
+        self.delegate
+            .as_mut()
+            .expect("Writer must be present until finished")
+            .flush()
+    }
+}
+
+impl<W: Write> Drop for EncoderWriter<W> {
+    fn drop(&mut self) {
+        if !self.panicked && self.delegate.is_some() {
+            let _ = self.write_final();
+        }
+    }
+}
+
+impl<W: Write> fmt::Debug for EncoderWriter<W> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("EncoderWriter")
+            .field("encoding", &self.encoding)
+            .field("chunk", &&self.chunk[..self.chunk_len])
+            .field("output_len", &self.output_len)
+            .field("panicked", &self.panicked)
+            .finish()
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn base32(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_base32"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // the command may exit without reading all of its input
+    let _ = child.stdin.take().unwrap().write_all(input);
+    child.wait_with_output().unwrap()
+}
+
+fn assert_output(output: &Output, code: i32, stdout: &[u8], stderr: &str) {
+    assert_eq!(output.status.code(), Some(code));
+    assert_eq!(output.stdout, stdout);
+    assert_eq!(String::from_utf8_lossy(&output.stderr), stderr);
+}
+
+#[test]
+fn encode() {
+    assert_output(&base32(&[], b""), 0, b"", "");
+    assert_output(&base32(&[], b"foobar"), 0, b"MZXW6YTBOI======\n", "");
+    assert_output(&base32(&["-w", "0"], b"foobar"), 0, b"MZXW6YTBOI======", "");
+    assert_output(
+        &base32(&["-w5"], b"foobar"),
+        0,
+        b"MZXW6\nYTBOI\n=====\n=\n",
+        "",
+    );
+    assert_output(
+        &base32(&["--wr=8", "-"], b"foobar"),
+        0,
+        b"MZXW6YTB\nOI======\n",
+        "",
+    );
+
+    let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+    let output = base32(&[], &data);
+    let encoded = base32::STANDARD.encode(&data);
+    let lines = encoded.as_bytes().chunks(76).collect::<Vec<_>>();
+    assert_eq!(output.stdout, [&lines.join(&b'\n')[..], b"\n"].concat());
+}
+
+#[test]
+fn alphabets() {
+    assert_output(
+        &base32(&["--base32hex"], b"foobar"),
+        0,
+        b"CPNMUOJ1E8======\n",
+        "",
+    );
+    assert_output(&base32(&["--crockford"], b"foobar"), 0, b"CSQPYRK1E8\n", "");
+    assert_output(&base32(&["--zbase32"], b"foobar"), 0, b"c3zs6aubqe\n", "");
+    assert_output(
+        &base32(&["--zbase32", "--base32"], b"foobar"),
+        0,
+        b"MZXW6YTBOI======\n",
+        "",
+    );
+    assert_output(
+        &base32(&["-d", "--crock"], b"csqp\nyrk1e8\n"),
+        0,
+        b"foobar",
+        "",
+    );
+}
+
+#[test]
+fn decode() {
+    assert_output(&base32(&["-d"], b"MZXW6YTB\nOI======\n"), 0, b"foobar", "");
+    assert_output(&base32(&["--decode"], b""), 0, b"", "");
+    assert_output(
+        &base32(&["-d"], b"MZXW6YTBOI\n"),
+        1,
+        b"fooba",
+        "base32: invalid input\n",
+    );
+    assert_output(
+        &base32(&["-d"], b"MZXW6YTB\r\n"),
+        1,
+        b"fooba",
+        "base32: invalid input\n",
+    );
+    assert_output(
+        &base32(&["-di"], b"MZ!XW 6YTB\r\nOI======"),
+        0,
+        b"foobar",
+        "",
+    );
+    assert_output(
+        &base32(&["-i", "-d", "--crockford"], b"CSQP-YRK1-E8"),
+        0,
+        b"foobar",
+        "",
+    );
+
+    // padding may end any chunk, not just the last one
+    assert_output(&base32(&["-d"], b"MZXW6===MZXW6===\n"), 0, b"foofoo", "");
+    assert_output(
+        &base32(&["-d"], b"MZXW6===\nMZXW6YTB\nOI======\n"),
+        0,
+        b"foofoobar",
+        "",
+    );
+
+    // whatever was decoded before invalid input is still written,
+    // including the bytes of a chunk up to its first invalid symbol
+    let mut input = b"A".repeat(9000);
+    input.extend_from_slice(b"!");
+    let output = base32(&["-d"], &input);
+    assert_output(&output, 1, &[0; 5625], "base32: invalid input\n");
+    assert_output(
+        &base32(&["-d"], b"MZXW6YTB\r\nOI======"),
+        1,
+        b"fooba",
+        "base32: invalid input\n",
+    );
+    assert_output(
+        &base32(&["-d"], b"MZXW6YT!"),
+        1,
+        b"foob",
+        "base32: invalid input\n",
+    );
+
+    // like coreutils, only uppercase is decoded, so lowercase is garbage
+    assert_output(
+        &base32(&["-d"], b"mzxw6==="),
+        1,
+        b"",
+        "base32: invalid input\n",
+    );
+    assert_output(&base32(&["-di"], b"MZxXW6==="), 0, b"foo", "");
+}
+
+#[test]
+fn usage_errors() {
+    let hint = "Try 'base32 --help' for more information.\n";
+
+    let cases: &[(&[&str], &str)] = &[
+        (&["-x"], "base32: invalid option -- 'x'\n"),
+        (&["--foo"], "base32: unrecognized option '--foo'\n"),
+        (&["-w"], "base32: option requires an argument -- 'w'\n"),
+        (
+            &["--wrap"],
+            "base32: option '--wrap' requires an argument\n",
+        ),
+        (
+            &["--decode=1"],
+            "base32: option '--decode' doesn't allow an argument\n",
+        ),
+        (
+            &["--base"],
+            "base32: option '--base' is ambiguous; possibilities: '--base32' '--base32hex'\n",
+        ),
+        (&["a", "b"], "base32: extra operand 'b'\n"),
+    ];
+    for (args, message) in cases {
+        assert_output(&base32(args, b""), 1, b"", &format!("{}{}", message, hint));
+    }
+
+    assert_output(
+        &base32(&["-w", "x"], b""),
+        1,
+        b"",
+        "base32: invalid wrap size: 'x'\n",
+    );
+    assert_output(
+        &base32(&["-w", "-1"], b""),
+        1,
+        b"",
+        "base32: invalid wrap size: '-1'\n",
+    );
+}
+
+#[test]
+fn files() {
+    let output = base32(&["does-not-exist"], b"");
+    assert_output(
+        &output,
+        1,
+        b"",
+        "base32: does-not-exist: No such file or directory\n",
+    );
+
+    let output = base32(&[env!("CARGO_MANIFEST_DIR")], b"");
+    assert_output(&output, 1, b"", "base32: read error: Is a directory\n");
+
+    let output = base32(
+        &["--", concat!(env!("CARGO_MANIFEST_DIR"), "/LICENSE")],
+        b"",
+    );
+    let license = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/LICENSE")).unwrap();
+    let decoded = base32(&["-d"], &output.stdout);
+    assert_output(&decoded, 0, &license, "");
+}
+
+#[test]
+fn help_and_version() {
+    let output = base32(&["--help", "-x"], b"");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output
+        .stdout
+        .starts_with(b"Usage: base32 [OPTION]... [FILE]\n"));
+
+    let output = base32(&["--vers"], b"");
+    let version = format!("base32 {}\n", env!("CARGO_PKG_VERSION"));
+    assert_output(&output, 0, version.as_bytes(), "");
+}